@@ -0,0 +1,458 @@
+use crate::content_filter::bm25::{bm25_scores, tokenize};
+use crate::crawler::AsyncWebCrawler;
+use crate::deep_crawl::normalize_url;
+use crate::models::{CrawlResult, CrawlerRunConfig, Link};
+use anyhow::{anyhow, Result};
+use kuchiki::traits::*;
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Number of recent pages whose new-term ratios are averaged for the saturation metric.
+const SATURATION_WINDOW: usize = 3;
+
+/// Configuration for [`AdaptiveCrawler`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveConfig {
+    /// Confidence at which the crawl stops (default: 0.7).
+    pub confidence_threshold: f64,
+    /// Maximum number of pages added to the knowledge base (default: 20).
+    pub max_pages: usize,
+    /// Number of links crawled per round (default: 3).
+    pub top_k_links: usize,
+    /// Minimum expected information gain of a link for it to be crawled (default: 0.1).
+    pub min_gain_threshold: f64,
+    /// Whether to follow links to other domains (default: false).
+    pub include_external: bool,
+    /// Whether to stem terms with the English stemmer (default: true).
+    pub use_stemming: bool,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        Self {
+            confidence_threshold: 0.7,
+            max_pages: 20,
+            top_k_links: 3,
+            min_gain_threshold: 0.1,
+            include_external: false,
+            use_stemming: true,
+        }
+    }
+}
+
+/// Term statistics of the pages crawled so far.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KnowledgeBase {
+    /// Tokenized text of each page, in crawl order.
+    pub documents: Vec<Vec<String>>,
+    /// Total occurrences of each term across all pages.
+    pub term_frequencies: HashMap<String, usize>,
+    /// Number of pages containing each term.
+    pub document_frequencies: HashMap<String, usize>,
+    /// Fraction of the distinct terms of each page that no earlier page contained.
+    pub new_term_ratios: Vec<f64>,
+}
+
+impl KnowledgeBase {
+    /// Adds the tokens of one page.
+    pub fn add_document(&mut self, tokens: Vec<String>) {
+        let distinct: HashSet<&String> = tokens.iter().collect();
+        let new_terms = distinct
+            .iter()
+            .filter(|term| !self.document_frequencies.contains_key(term.as_str()))
+            .count();
+        self.new_term_ratios.push(if distinct.is_empty() {
+            0.0
+        } else {
+            new_terms as f64 / distinct.len() as f64
+        });
+
+        for term in distinct {
+            *self.document_frequencies.entry(term.clone()).or_default() += 1;
+        }
+        for term in &tokens {
+            *self.term_frequencies.entry(term.clone()).or_default() += 1;
+        }
+        self.documents.push(tokens);
+    }
+
+    /// Returns how well `term` is covered. Each page containing it halves the remaining
+    /// uncertainty, so one page gives 0.5, two give 0.75 and so on.
+    pub fn term_coverage(&self, term: &str) -> f64 {
+        let df = self.document_frequencies.get(term).copied().unwrap_or(0);
+        1.0 - 0.5f64.powi(df.min(i32::MAX as usize) as i32)
+    }
+
+    /// Computes the sufficiency metrics for the query `terms`.
+    pub fn metrics(&self, terms: &[String]) -> AdaptiveMetrics {
+        let coverage = if terms.is_empty() {
+            0.0
+        } else {
+            terms.iter().map(|t| self.term_coverage(t)).sum::<f64>() / terms.len() as f64
+        };
+
+        // Pages agree when they mention the same aspects of the query. A single page has
+        // nothing to agree with.
+        let aspects: Vec<HashSet<&String>> = self
+            .documents
+            .iter()
+            .map(|doc| terms.iter().filter(|t| doc.contains(t)).collect::<HashSet<_>>())
+            .filter(|found| !found.is_empty())
+            .collect();
+        let consistency = match aspects.len() {
+            0 | 1 => 0.0,
+            n => {
+                let mut total = 0.0;
+                for i in 0..n {
+                    for j in i + 1..n {
+                        let shared = aspects[i].intersection(&aspects[j]).count();
+                        total += shared as f64 / aspects[i].union(&aspects[j]).count() as f64;
+                    }
+                }
+                total / (n * (n - 1) / 2) as f64
+            }
+        };
+
+        let recent = &self.new_term_ratios[self.new_term_ratios.len().saturating_sub(SATURATION_WINDOW)..];
+        let saturation = if recent.is_empty() {
+            0.0
+        } else {
+            1.0 - recent.iter().sum::<f64>() / recent.len() as f64
+        };
+
+        AdaptiveMetrics::new(coverage, consistency, saturation)
+    }
+}
+
+/// How sufficient the gathered information is for the query, each between 0.0 and 1.0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveMetrics {
+    /// How many crawled pages mention each query term.
+    pub coverage: f64,
+    /// How much the relevant pages agree on which query terms they cover.
+    pub consistency: f64,
+    /// How few new terms the latest pages contributed.
+    pub saturation: f64,
+    /// Weighted combination of the metrics above.
+    pub confidence: f64,
+}
+
+impl AdaptiveMetrics {
+    fn new(coverage: f64, consistency: f64, saturation: f64) -> Self {
+        Self {
+            coverage,
+            consistency,
+            saturation,
+            confidence: 0.5 * coverage + 0.3 * consistency + 0.2 * saturation,
+        }
+    }
+}
+
+/// Why an adaptive crawl stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// The confidence threshold was reached.
+    ConfidenceReached,
+    /// `max_pages` pages were crawled.
+    MaxPages,
+    /// No uncrawled links are left.
+    NoLinks,
+    /// No remaining link is expected to add enough information.
+    LowGain,
+}
+
+/// Outcome of an adaptive crawl.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveCrawlState {
+    /// The query the crawl gathered information for.
+    pub query: String,
+    /// Tokenized query terms.
+    pub query_terms: Vec<String>,
+    /// Successfully crawled pages, in crawl order.
+    pub pages: Vec<CrawlResult>,
+    /// Term statistics of `pages`.
+    pub knowledge_base: KnowledgeBase,
+    /// Metrics after the last page was added.
+    pub metrics: AdaptiveMetrics,
+    /// Why the crawl stopped.
+    pub stop_reason: Option<StopReason>,
+}
+
+impl AdaptiveCrawlState {
+    /// Returns the confidence that enough information was gathered.
+    pub fn confidence(&self) -> f64 {
+        self.metrics.confidence
+    }
+
+    /// Returns up to `k` pages ranked by BM25 relevance to the query, with their scores.
+    /// Pages that do not mention the query are left out.
+    pub fn relevant_pages(&self, k: usize) -> Vec<(&CrawlResult, f32)> {
+        let scores = bm25_scores(&self.knowledge_base.documents, &self.query_terms);
+        let mut ranked: Vec<_> = self.pages.iter().zip(scores).filter(|(_, score)| *score > 0.0).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(k);
+        ranked
+    }
+}
+
+/// Crawls toward a query and stops once the gathered pages are likely to answer it.
+///
+/// Starting from one page, each round crawls the `top_k_links` pending links with the
+/// highest expected information gain: the share of query terms a link mentions in its
+/// URL, text or title, weighted by how poorly each term is covered so far.
+pub struct AdaptiveCrawler<'a> {
+    crawler: &'a AsyncWebCrawler,
+    config: AdaptiveConfig,
+    run_config: Option<CrawlerRunConfig>,
+    stemmer: Option<Stemmer>,
+}
+
+impl<'a> AdaptiveCrawler<'a> {
+    /// Creates a new `AdaptiveCrawler` crawling with `crawler`.
+    pub fn new(crawler: &'a AsyncWebCrawler, config: AdaptiveConfig) -> Self {
+        let stemmer = config.use_stemming.then(|| Stemmer::create(Algorithm::English));
+        Self {
+            crawler,
+            config,
+            run_config: None,
+            stemmer,
+        }
+    }
+
+    /// Sets the run configuration used for every page.
+    pub fn with_run_config(mut self, run_config: CrawlerRunConfig) -> Self {
+        self.run_config = Some(run_config);
+        self
+    }
+
+    /// Crawls from `start_url` until enough information about `query` has been gathered.
+    ///
+    /// Fails if the start page cannot be crawled. Later pages that fail are skipped.
+    pub async fn digest(&self, start_url: &str, query: &str) -> Result<AdaptiveCrawlState> {
+        let mut query_terms = Vec::new();
+        for term in tokenize(query, self.stemmer.as_ref()) {
+            if !query_terms.contains(&term) {
+                query_terms.push(term);
+            }
+        }
+
+        let mut state = AdaptiveCrawlState {
+            query: query.to_string(),
+            query_terms,
+            pages: Vec::new(),
+            knowledge_base: KnowledgeBase::default(),
+            metrics: AdaptiveMetrics::default(),
+            stop_reason: None,
+        };
+        let mut pending: Vec<Link> = Vec::new();
+        let mut seen = HashSet::from([normalize_url(start_url, None).unwrap_or_else(|| start_url.to_string())]);
+
+        let start = self.crawler.arun(start_url, self.run_config.clone()).await?;
+        if !start.success {
+            return Err(anyhow!(
+                "Failed to crawl start page {}: {}",
+                start_url,
+                start.error_message.unwrap_or_default()
+            ));
+        }
+        self.add_page(&mut state, &mut pending, &mut seen, start);
+
+        let stop_reason = loop {
+            if state.metrics.confidence >= self.config.confidence_threshold {
+                break StopReason::ConfidenceReached;
+            }
+            if state.pages.len() >= self.config.max_pages {
+                break StopReason::MaxPages;
+            }
+            if pending.is_empty() {
+                break StopReason::NoLinks;
+            }
+
+            let batch = self.next_links(&state, &mut pending);
+            if batch.is_empty() {
+                break StopReason::LowGain;
+            }
+
+            let urls: Vec<String> = batch.into_iter().filter_map(|link| link.href).collect();
+            for result in self.crawler.arun_many(urls, self.run_config.clone(), None).await? {
+                if result.success {
+                    self.add_page(&mut state, &mut pending, &mut seen, result);
+                }
+            }
+        };
+        state.stop_reason = Some(stop_reason);
+
+        Ok(state)
+    }
+
+    /// Returns the expected information gain of crawling `link`.
+    fn expected_gain(&self, link: &Link, state: &AdaptiveCrawlState) -> f64 {
+        if state.query_terms.is_empty() {
+            return 0.0;
+        }
+
+        let preview = [&link.href, &link.text, &link.title]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let tokens: HashSet<String> = tokenize(&preview, self.stemmer.as_ref()).into_iter().collect();
+
+        let gain: f64 = state
+            .query_terms
+            .iter()
+            .filter(|term| tokens.contains(*term))
+            .map(|term| 1.0 - state.knowledge_base.term_coverage(term))
+            .sum();
+        gain / state.query_terms.len() as f64
+    }
+
+    /// Removes and returns the links to crawl next, best first.
+    fn next_links(&self, state: &AdaptiveCrawlState, pending: &mut Vec<Link>) -> Vec<Link> {
+        let budget = self
+            .config
+            .top_k_links
+            .min(self.config.max_pages.saturating_sub(state.pages.len()));
+
+        let mut ranked: Vec<(f64, Link)> = pending
+            .drain(..)
+            .map(|link| (self.expected_gain(&link, state), link))
+            .collect();
+        // Stable, so that equal gains keep discovery order
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut batch = Vec::new();
+        for (gain, link) in ranked {
+            if batch.len() < budget && gain >= self.config.min_gain_threshold {
+                batch.push(link);
+            } else {
+                pending.push(link);
+            }
+        }
+        batch
+    }
+
+    fn add_page(
+        &self,
+        state: &mut AdaptiveCrawlState,
+        pending: &mut Vec<Link>,
+        seen: &mut HashSet<String>,
+        result: CrawlResult,
+    ) {
+        let text = page_text(&result.html);
+        state.knowledge_base.add_document(tokenize(&text, self.stemmer.as_ref()));
+        state.metrics = state.knowledge_base.metrics(&state.query_terms);
+
+        if let Some(ref links) = result.links {
+            let mut groups = vec!["internal"];
+            if self.config.include_external {
+                groups.push("external");
+            }
+            // Relative links are relative to the page the crawl was redirected to
+            let base = result.redirected_url.as_deref().unwrap_or(&result.url);
+            for link in groups.into_iter().filter_map(|g| links.get(g)).flatten() {
+                let Some(url) = link.href.as_deref().and_then(|href| normalize_url(href, Some(base))) else {
+                    continue;
+                };
+                if seen.insert(url.clone()) {
+                    pending.push(Link {
+                        href: Some(url),
+                        text: link.text.clone(),
+                        title: link.title.clone(),
+                    });
+                }
+            }
+        }
+
+        state.pages.push(result);
+    }
+}
+
+/// Returns the visible text of `html`. Plain text rather than markdown, whose link targets
+/// would add URL fragments as terms; scripts, styles and the like are dropped first.
+fn page_text(html: &str) -> String {
+    let document = kuchiki::parse_html().one(html);
+    if let Ok(hidden) = document.select("script, style, noscript, template") {
+        for node in hidden.collect::<Vec<_>>() {
+            node.as_node().detach();
+        }
+    }
+    document.text_contents()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(text: &str) -> Vec<String> {
+        tokenize(text, None)
+    }
+
+    #[test]
+    fn test_knowledge_base_metrics() {
+        let query = tokens("tokio runtime");
+        let mut kb = KnowledgeBase::default();
+        assert_eq!(kb.metrics(&query), AdaptiveMetrics::default());
+
+        kb.add_document(tokens("the tokio runtime schedules tasks"));
+        let first = kb.metrics(&query);
+        assert_eq!(first.coverage, 0.5);
+        assert_eq!(first.consistency, 0.0);
+        assert_eq!(first.saturation, 0.0);
+        assert_eq!(first.confidence, 0.25);
+
+        kb.add_document(tokens("tasks are scheduled by the runtime"));
+        let second = kb.metrics(&query);
+        // "tokio" is on one page, "runtime" on both
+        assert_eq!(second.coverage, 0.625);
+        assert_eq!(second.consistency, 0.5);
+        // 3 of the 6 distinct terms of the second page are new
+        assert_eq!(kb.new_term_ratios, vec![1.0, 0.5]);
+        assert_eq!(second.saturation, 0.25);
+        assert!((second.confidence - 0.5125).abs() < 1e-9);
+        assert_eq!(kb.term_frequencies["runtime"], 2);
+        assert_eq!(kb.document_frequencies["tasks"], 2);
+    }
+
+    #[test]
+    fn test_page_text_skips_scripts_and_styles() {
+        let html = r#"<html><head><style>.tokio { color: red }</style></head><body>
+            <p>Async runtime</p><script>var tokio = 1;</script><noscript>Enable JavaScript</noscript>
+        </body></html>"#;
+        assert_eq!(tokens(&page_text(html)), tokens("Async runtime"));
+    }
+
+    #[test]
+    fn test_links_resolve_against_redirected_url() {
+        let crawler = AsyncWebCrawler::new();
+        let adaptive = AdaptiveCrawler::new(&crawler, AdaptiveConfig::default());
+        let mut state = AdaptiveCrawlState {
+            query: "guide".to_string(),
+            query_terms: tokens("guide"),
+            pages: Vec::new(),
+            knowledge_base: KnowledgeBase::default(),
+            metrics: AdaptiveMetrics::default(),
+            stop_reason: None,
+        };
+        let link = Link {
+            href: Some("guide".to_string()),
+            text: Some("Guide".to_string()),
+            title: None,
+        };
+        let result = CrawlResult {
+            url: "https://example.com/docs".to_string(),
+            redirected_url: Some("https://example.com/docs/".to_string()),
+            links: Some(HashMap::from([("internal".to_string(), vec![link])])),
+            ..Default::default()
+        };
+
+        let (mut pending, mut seen) = (Vec::new(), HashSet::new());
+        adaptive.add_page(&mut state, &mut pending, &mut seen, result);
+
+        assert_eq!(pending[0].href.as_deref(), Some("https://example.com/docs/guide"));
+    }
+}
@@ -0,0 +1,276 @@
+use crate::models::{BrowserConfig, CrawlResult, CrawlerRunConfig, CrawlerStrategy};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use url::Url;
+
+/// An on-disk store of `CrawlResult`s, one JSON file per cache key.
+///
+/// Keys combine the URL with a fingerprint of the run options that change what the
+/// browser returns (wait strategy, screenshot), of how the page is fetched (browser or plain
+/// HTTP) and of who fetches it (session, proxy, cookies, storage state, user agent, locale,
+/// request headers), so that pages fetched logged in are
+/// never served to anonymous crawls and the reverse. Options that only post-process the
+/// HTML, such as content filters and extraction strategies, are not part of the key,
+/// so they can be re-run against a cached page without touching the browser.
+#[derive(Debug, Clone)]
+pub struct CrawlCache {
+    dir: PathBuf,
+}
+
+impl Default for CrawlCache {
+    fn default() -> Self {
+        Self::new(Self::default_dir())
+    }
+}
+
+impl CrawlCache {
+    /// Creates a new `CrawlCache` stored in `dir`. The directory is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns `$CRAWL4_AI_BASE_DIRECTORY/.crawl4ai/cache`, falling back to the home directory.
+    pub fn default_dir() -> PathBuf {
+        let base = env::var("CRAWL4_AI_BASE_DIRECTORY")
+            .or_else(|_| env::var("HOME"))
+            .or_else(|_| env::var("USERPROFILE"))
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| env::temp_dir());
+        base.join(".crawl4ai").join("cache")
+    }
+
+    /// Returns the directory the cache is stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns whether results for `url` can be cached. Only HTTP(S) pages are cached.
+    pub fn is_cacheable(url: &str) -> bool {
+        Url::parse(url)
+            .map(|u| matches!(u.scheme(), "http" | "https"))
+            .unwrap_or(false)
+    }
+
+    /// Computes the cache key for crawling `url` with `config`, using `strategy` on a browser
+    /// configured with `browser`.
+    pub fn cache_key(
+        url: &str,
+        config: &Option<CrawlerRunConfig>,
+        browser: &BrowserConfig,
+        strategy: &CrawlerStrategy,
+    ) -> String {
+        let default_config = CrawlerRunConfig::default();
+        let cfg = config.as_ref().unwrap_or(&default_config);
+        let fingerprint = json!({
+            // Plain HTTP pages have not run any JavaScript
+            "strategy": match strategy {
+                CrawlerStrategy::Browser => json!("browser"),
+                CrawlerStrategy::Http(http) => json!({ "type": "http", "headers": headers_digest(&http.headers) }),
+            },
+            // Who the page is fetched as
+            "session_id": cfg.session_id,
+            "proxy": cfg.proxy.as_ref().or(browser.proxy.as_ref()),
+            "cookies": browser.cookies,
+            "storage_state": browser.storage_state,
+            "user_agent": browser.user_agent,
+            "locale": browser.locale,
+            "timezone": browser.timezone,
+            "user_data_dir": browser.user_data_dir,
+            "wait_for": cfg.wait_for,
+            "screenshot": cfg.screenshot,
+            "js_code": cfg.js_code,
+            // Blocked scripts and stylesheets can change the rendered HTML
+            "block_resources": cfg.block_resources,
+            // Links and media are extracted from the selected elements only
+            "css_selector": cfg.css_selector,
+        });
+
+        let mut hasher = Sha256::new();
+        hasher.update(url.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(fingerprint.to_string().as_bytes());
+        hex(&hasher.finalize())
+    }
+
+    /// Returns the cached result for `key`, if any.
+    pub async fn get(&self, key: &str) -> Option<CrawlResult> {
+        let content = tokio::fs::read(self.path_for(key)).await.ok()?;
+        match serde_json::from_slice(&content) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                eprintln!("Ignoring corrupt cache entry {}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Stores `result` under `key`.
+    pub async fn set(&self, key: &str, result: &CrawlResult) -> anyhow::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        // Write to a temporary file first so readers never see a partial entry. Its name is
+        // unique so that concurrent writers of the same key do not interleave.
+        static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+        let path = self.path_for(key);
+        let tmp = path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        if let Err(e) = tokio::fs::write(&tmp, serde_json::to_vec(result)?).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(e.into());
+        }
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    /// Removes the cached result for `key`.
+    pub async fn remove(&self, key: &str) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.path_for(key)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes every cached result.
+    pub async fn clear(&self) -> anyhow::Result<()> {
+        match tokio::fs::remove_dir_all(&self.dir).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+/// Hashes request headers, which may carry credentials, independently of name case and order.
+fn headers_digest(headers: &HashMap<String, String>) -> String {
+    let sorted: BTreeMap<String, &String> = headers.iter().map(|(name, value)| (name.to_lowercase(), value)).collect();
+    let mut hasher = Sha256::new();
+    for (name, value) in sorted {
+        hasher.update(name.as_bytes());
+        hasher.update(b": ");
+        hasher.update(value.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex(&hasher.finalize())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HttpCrawlerConfig, WaitStrategy};
+
+    #[test]
+    fn test_cache_key_fingerprint() {
+        let url = "https://example.com/";
+        let browser = BrowserConfig::default();
+        let base = CrawlCache::cache_key(url, &None, &browser, &CrawlerStrategy::Browser);
+
+        // Post-processing options do not change the key
+        let filtered = Some(CrawlerRunConfig {
+            retry_404: true,
+            ..Default::default()
+        });
+        assert_eq!(base, CrawlCache::cache_key(url, &filtered, &browser, &CrawlerStrategy::Browser));
+
+        // Options that change the fetched page do
+        let waiting = Some(CrawlerRunConfig {
+            wait_for: Some(WaitStrategy::Fixed(100)),
+            ..Default::default()
+        });
+        assert_ne!(base, CrawlCache::cache_key(url, &waiting, &browser, &CrawlerStrategy::Browser));
+        assert_ne!(base, CrawlCache::cache_key("https://example.com/other", &None, &browser, &CrawlerStrategy::Browser));
+
+        // So does who the page is fetched as
+        let in_session = Some(CrawlerRunConfig {
+            session_id: Some("logged_in".to_string()),
+            ..Default::default()
+        });
+        assert_ne!(base, CrawlCache::cache_key(url, &in_session, &browser, &CrawlerStrategy::Browser));
+        let with_cookies = BrowserConfig {
+            cookies: vec![serde_json::from_value(json!({"name": "sid", "value": "abc", "url": url})).unwrap()],
+            ..Default::default()
+        };
+        assert_ne!(base, CrawlCache::cache_key(url, &None, &with_cookies, &CrawlerStrategy::Browser));
+
+        // And how: over plain HTTP, and with which request headers
+        let http = HttpCrawlerConfig::default();
+        let plain = CrawlCache::cache_key(url, &None, &browser, &CrawlerStrategy::Http(http.clone()));
+        assert_ne!(base, plain);
+        let mut authorized = http.clone();
+        authorized.headers.insert("Authorization".to_string(), "Bearer secret".to_string());
+        let with_auth = CrawlCache::cache_key(url, &None, &browser, &CrawlerStrategy::Http(authorized.clone()));
+        assert_ne!(plain, with_auth);
+        authorized.headers = [("authorization".to_string(), "Bearer secret".to_string())].into();
+        assert_eq!(with_auth, CrawlCache::cache_key(url, &None, &browser, &CrawlerStrategy::Http(authorized)));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes_of_one_key() {
+        let dir = env::temp_dir().join(format!("crawl4ai-cache-concurrent-{}", std::process::id()));
+        let cache = CrawlCache::new(&dir);
+        let key = CrawlCache::cache_key("https://example.com", &None, &BrowserConfig::default(), &CrawlerStrategy::Browser);
+
+        let writes = (0..8).map(|i| {
+            let (cache, key) = (cache.clone(), key.clone());
+            tokio::spawn(async move {
+                let result = CrawlResult {
+                    html: format!("<p>{}</p>", "x".repeat(10_000 * (i + 1))),
+                    ..Default::default()
+                };
+                cache.set(&key, &result).await
+            })
+        });
+        for write in futures::future::join_all(writes).await {
+            write.unwrap().unwrap();
+        }
+
+        assert!(cache.get(&key).await.is_some());
+        cache.clear().await.unwrap();
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        assert!(CrawlCache::is_cacheable("https://example.com"));
+        assert!(!CrawlCache::is_cacheable("data:text/html,<p>hi</p>"));
+        assert!(!CrawlCache::is_cacheable("not a url"));
+    }
+
+    #[tokio::test]
+    async fn test_roundtrip() {
+        let dir = env::temp_dir().join(format!("crawl4ai-cache-test-{}", std::process::id()));
+        let cache = CrawlCache::new(&dir);
+        let key = CrawlCache::cache_key("https://example.com", &None, &BrowserConfig::default(), &CrawlerStrategy::Browser);
+
+        assert!(cache.get(&key).await.is_none());
+
+        let result = CrawlResult {
+            url: "https://example.com".to_string(),
+            html: "<p>cached</p>".to_string(),
+            success: true,
+            ..Default::default()
+        };
+        cache.set(&key, &result).await.unwrap();
+
+        let cached = cache.get(&key).await.unwrap();
+        assert_eq!(cached.html, "<p>cached</p>");
+
+        cache.remove(&key).await.unwrap();
+        assert!(cache.get(&key).await.is_none());
+
+        cache.clear().await.unwrap();
+        assert!(!dir.exists());
+    }
+}
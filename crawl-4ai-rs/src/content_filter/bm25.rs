@@ -65,19 +65,7 @@ impl BM25ContentFilter {
             None
         };
 
-        let tokenize = |text: &str| -> Vec<String> {
-            let tokens = text.to_lowercase()
-                .split(|c: char| !c.is_alphanumeric())
-                .filter(|s| !s.is_empty())
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>();
-
-            if let Some(s) = &stemmer {
-                tokens.into_iter().map(|t| s.stem(&t).to_string()).collect()
-            } else {
-                tokens
-            }
-        };
+        let tokenize = |text: &str| tokenize(text, stemmer.as_ref());
 
         let tokenized_query = tokenize(&query);
         let tokenized_corpus: Vec<Vec<String>> = candidates.iter()
@@ -85,7 +73,7 @@ impl BM25ContentFilter {
             .collect();
 
         // Calculate BM25 Scores
-        let scores = self.calculate_bm25(&tokenized_corpus, &tokenized_query);
+        let scores = bm25_scores(&tokenized_corpus, &tokenized_query);
 
         // Adjust scores with tag weights
         let priority_tags: HashMap<&str, f32> = [
@@ -189,7 +177,7 @@ impl BM25ContentFilter {
 
                          let is_inline = inline_tags.contains(tag_name.as_str());
                          // Python: not (tag.name == "p" and len(current_text) == 0)
-                         let should_break = !is_inline && !(tag_name == "p" && current_text.is_empty());
+                         let should_break = !is_inline && (tag_name != "p" || !current_text.is_empty());
 
                          if should_break {
                              let text = current_text.join(" ");
@@ -229,42 +217,57 @@ impl BM25ContentFilter {
 
         chunks
     }
+}
 
-    fn calculate_bm25(&self, corpus: &[Vec<String>], query: &[String]) -> Vec<f32> {
-        let n = corpus.len() as f32;
-        if n == 0.0 { return vec![]; }
-        let avgdl: f32 = corpus.iter().map(|d| d.len()).sum::<usize>() as f32 / n;
+/// Splits `text` into lowercase alphanumeric tokens, stemmed if a stemmer is given.
+pub(crate) fn tokenize(text: &str, stemmer: Option<&Stemmer>) -> Vec<String> {
+    let tokens = text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+
+    if let Some(s) = stemmer {
+        tokens.into_iter().map(|t| s.stem(&t).to_string()).collect()
+    } else {
+        tokens
+    }
+}
 
-        let k1 = 1.5;
-        let b = 0.75;
+/// Scores each tokenized document of `corpus` against `query` with Okapi BM25.
+pub(crate) fn bm25_scores(corpus: &[Vec<String>], query: &[String]) -> Vec<f32> {
+    let n = corpus.len() as f32;
+    if n == 0.0 { return vec![]; }
+    let avgdl: f32 = corpus.iter().map(|d| d.len()).sum::<usize>() as f32 / n;
 
-        let mut scores = vec![0.0; corpus.len()];
+    let k1 = 1.5;
+    let b = 0.75;
 
-        for term in query {
-            // Calculate IDF for term
-            let doc_freq = corpus.iter().filter(|d| d.contains(term)).count() as f32;
-            let idf = ((n - doc_freq + 0.5) / (doc_freq + 0.5) + 1.0).ln();
+    let mut scores = vec![0.0; corpus.len()];
 
-            for (i, doc) in corpus.iter().enumerate() {
-                let term_freq = doc.iter().filter(|&t| t == term).count() as f32;
-                let doc_len = doc.len() as f32;
+    for term in query {
+        // Calculate IDF for term
+        let doc_freq = corpus.iter().filter(|d| d.contains(term)).count() as f32;
+        let idf = ((n - doc_freq + 0.5) / (doc_freq + 0.5) + 1.0).ln();
 
-                if term_freq > 0.0 {
-                    let numerator = term_freq * (k1 + 1.0);
-                    let denominator = term_freq + k1 * (1.0 - b + b * (doc_len / avgdl));
-                    scores[i] += idf * (numerator / denominator);
-                }
+        for (i, doc) in corpus.iter().enumerate() {
+            let term_freq = doc.iter().filter(|&t| t == term).count() as f32;
+            let doc_len = doc.len() as f32;
+
+            if term_freq > 0.0 {
+                let numerator = term_freq * (k1 + 1.0);
+                let denominator = term_freq + k1 * (1.0 - b + b * (doc_len / avgdl));
+                scores[i] += idf * (numerator / denominator);
             }
         }
-
-        scores
     }
+
+    scores
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use kuchiki::traits::*;
 
     #[test]
     fn test_extract_text_chunks_nested() {
@@ -0,0 +1,224 @@
+use kuchiki::traits::*;
+use kuchiki::NodeRef;
+use serde::{Deserialize, Serialize};
+
+/// Elements that never carry page content and are always removed.
+const REMOVED_TAGS: &[&str] = &["head", "script", "style", "noscript", "template", "link", "meta"];
+
+/// Text blocks that are dropped when they have fewer than `word_count_threshold` words.
+const TEXT_BLOCK_TAGS: &[&str] = &[
+    "p", "li", "blockquote", "dd", "dt", "figcaption", "span", "label", "caption", "summary",
+];
+
+/// Elements that are meaningful without text content and are never dropped as empty.
+const CONTENT_TAGS: &[&str] = &[
+    "img", "picture", "source", "video", "audio", "iframe", "svg", "canvas", "object", "embed", "br",
+    "hr", "input", "select", "textarea", "button", "td", "th", "tr", "col", "colgroup",
+];
+
+/// Cleans page HTML for `CrawlResult::cleaned_html`.
+///
+/// Scripts, styles and other non-content elements are removed, as are `excluded_tags`,
+/// `excluded_selector` matches, short text blocks and elements left empty. Only the
+/// attributes in `keep_attrs` are kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentScrapingStrategy {
+    /// Minimum number of words for paragraphs, list items and similar text blocks to be
+    /// kept. Headings and blocks containing media are always kept (default: 1).
+    pub word_count_threshold: usize,
+    /// Tag names to remove with their content, e.g. `["nav", "footer"]` (default: none).
+    pub excluded_tags: Vec<String>,
+    /// CSS selector of elements to remove with their content (default: none).
+    pub excluded_selector: Option<String>,
+    /// Whether to remove `<form>` elements (default: false).
+    pub remove_forms: bool,
+    /// Attributes kept on the remaining elements (default: `href`, `src`, `srcset`, `alt`,
+    /// `title`, `colspan`, `rowspan`, `datetime`).
+    pub keep_attrs: Vec<String>,
+}
+
+impl Default for ContentScrapingStrategy {
+    fn default() -> Self {
+        Self {
+            word_count_threshold: 1,
+            excluded_tags: Vec::new(),
+            excluded_selector: None,
+            remove_forms: false,
+            keep_attrs: ["href", "src", "srcset", "alt", "title", "colspan", "rowspan", "datetime"]
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl ContentScrapingStrategy {
+    /// Returns the cleaned content of the `<body>` of `html`.
+    pub fn scrape(&self, html: &str) -> String {
+        let document = kuchiki::parse_html().one(html);
+        let root = document.select_first("body").map(|b| b.as_node().clone()).unwrap_or(document);
+
+        let mut selectors: Vec<String> = REMOVED_TAGS.iter().map(|t| t.to_string()).collect();
+        selectors.extend(self.excluded_tags.iter().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()));
+        if self.remove_forms {
+            selectors.push("form".to_string());
+        }
+        selectors.extend(self.excluded_selector.clone().filter(|s| !s.trim().is_empty()));
+        for selector in selectors {
+            match root.select(&selector) {
+                Ok(nodes) => nodes.collect::<Vec<_>>().into_iter().for_each(|n| n.as_node().detach()),
+                Err(()) => eprintln!("Ignoring invalid excluded selector: {}", selector),
+            }
+        }
+
+        // Children come before their parents in reverse document order, so wrappers that
+        // only become empty once their children are removed are dropped too
+        let nodes: Vec<NodeRef> = root.descendants().collect();
+        for node in nodes.into_iter().rev() {
+            if node.as_comment().is_some() {
+                node.detach();
+                continue;
+            }
+            let Some(element) = node.as_element() else { continue };
+            let tag = element.name.local.to_string();
+
+            if !CONTENT_TAGS.contains(&tag.as_str()) && !has_media(&node) {
+                let words = node.text_contents().split_whitespace().count();
+                let too_short = TEXT_BLOCK_TAGS.contains(&tag.as_str()) && words < self.word_count_threshold;
+                if words == 0 || too_short {
+                    node.detach();
+                    continue;
+                }
+            }
+
+            element
+                .attributes
+                .borrow_mut()
+                .map
+                .retain(|name, _| self.keep_attrs.iter().any(|keep| keep.eq_ignore_ascii_case(&name.local)));
+        }
+
+        let mut html = Vec::new();
+        for child in root.children() {
+            let _ = child.serialize(&mut html);
+        }
+        String::from_utf8_lossy(&html).trim().to_string()
+    }
+}
+
+/// Returns a document holding only the elements of `html` that match `selector`, in
+/// document order. Matches nested in other matches are not repeated, and the `<base>`
+/// element is kept so that relative URLs resolve as they did on the page.
+pub fn select_content(html: &str, selector: &str) -> String {
+    let document = kuchiki::parse_html().one(html);
+    let matches: Vec<NodeRef> = match document.select(selector) {
+        Ok(nodes) => nodes.map(|n| n.as_node().clone()).collect(),
+        Err(()) => {
+            eprintln!("Ignoring invalid selector: {}", selector);
+            Vec::new()
+        }
+    };
+    if matches.is_empty() {
+        eprintln!("No elements match selector: {}", selector);
+    }
+
+    let mut head = Vec::new();
+    if let Ok(base) = document.select_first("base[href]") {
+        let _ = base.as_node().serialize(&mut head);
+    }
+    let mut body = Vec::new();
+    for node in &matches {
+        if !node.ancestors().any(|ancestor| matches.contains(&ancestor)) {
+            let _ = node.serialize(&mut body);
+        }
+    }
+
+    format!(
+        "<html><head>{}</head><body>{}</body></html>",
+        String::from_utf8_lossy(&head),
+        String::from_utf8_lossy(&body)
+    )
+}
+
+fn has_media(node: &NodeRef) -> bool {
+    node.descendants()
+        .filter_map(|n| n.as_element().map(|e| e.name.local.to_string()))
+        .any(|tag| CONTENT_TAGS.contains(&tag.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"
+        <html><head><title>Page</title><style>p { color: red }</style></head>
+        <body>
+            <nav class="menu"><a href="/">Home</a></nav>
+            <div id="main" class="content" onclick="track()">
+                <h1 class="title">Title</h1>
+                <!-- a comment -->
+                <p style="margin: 0">A paragraph with <a href="/more" class="link">enough words</a> in it.</p>
+                <p>Too short</p>
+                <div class="ad" data-slot="1">Buy now</div>
+                <div><span> </span><div></div></div>
+                <p><img src="cat.png" alt="Cat" width="10"></p>
+                <script>track()</script>
+            </div>
+        </body></html>
+    "#;
+
+    fn compact(html: &str) -> String {
+        html.split_whitespace().collect::<Vec<_>>().join(" ").replace("> <", "><")
+    }
+
+    #[test]
+    fn test_default_cleaning() {
+        let cleaned = ContentScrapingStrategy::default().scrape(PAGE);
+
+        assert!(!cleaned.contains("<script") && !cleaned.contains("<style") && !cleaned.contains("comment"));
+        assert!(!cleaned.contains("class=") && !cleaned.contains("onclick") && !cleaned.contains("width"));
+        assert!(cleaned.contains(r#"<a href="/more">enough words</a>"#));
+        assert!(cleaned.contains(r#"<img alt="Cat" src="cat.png">"#) || cleaned.contains(r#"<img src="cat.png" alt="Cat">"#));
+        // The nested empty wrappers are gone entirely
+        assert!(!compact(&cleaned).contains("<div></div>"));
+        assert!(!cleaned.contains("<span>"));
+        assert!(cleaned.contains("Too short"));
+    }
+
+    #[test]
+    fn test_select_content() {
+        let html = r#"<html><head><base href="https://example.com/docs/"></head><body>
+            <nav><a href="/">Home</a></nav>
+            <article><h1>First</h1><article><p>Nested</p></article></article>
+            <article><p>Second</p></article>
+        </body></html>"#;
+
+        let selected = select_content(html, "article");
+        assert!(selected.contains(r#"<base href="https://example.com/docs/">"#));
+        assert!(!selected.contains("Home"));
+        assert_eq!(selected.matches("Nested").count(), 1);
+        assert!(selected.find("First").unwrap() < selected.find("Second").unwrap());
+
+        assert_eq!(select_content(html, "#missing"), "<html><head><base href=\"https://example.com/docs/\"></head><body></body></html>");
+    }
+
+    #[test]
+    fn test_exclusions_and_word_threshold() {
+        let strategy = ContentScrapingStrategy {
+            word_count_threshold: 3,
+            excluded_tags: vec!["nav".to_string()],
+            excluded_selector: Some(".ad, #missing".to_string()),
+            ..Default::default()
+        };
+        let cleaned = strategy.scrape(PAGE);
+
+        assert!(!cleaned.contains("Home"));
+        assert!(!cleaned.contains("Buy now"));
+        assert!(!cleaned.contains("Too short"));
+        // Headings and images are kept regardless of their word count
+        assert!(cleaned.contains("<h1>Title</h1>"));
+        assert!(cleaned.contains("cat.png"));
+        assert!(cleaned.contains("enough words"));
+    }
+}
@@ -1,17 +1,37 @@
-use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::browser::{Browser, BrowserConfig as ChromeConfig};
+use chromiumoxide::handler::viewport::Viewport;
 use chromiumoxide::cdp::browser_protocol::target::{CreateBrowserContextParams, CreateTargetParams};
 use chromiumoxide::cdp::browser_protocol::network::{self, EventRequestWillBeSent, EventLoadingFinished, EventLoadingFailed};
 use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
-use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
+use chromiumoxide::cdp::browser_protocol::page::{AddScriptToEvaluateOnNewDocumentParams, CaptureScreenshotFormat};
+use chromiumoxide::cdp::browser_protocol::storage::{GetCookiesParams, SetCookiesParams};
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    self, AuthChallengeResponse, AuthChallengeResponseResponse, AuthChallengeSource, RequestPattern,
+};
+use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
 use chromiumoxide::page::ScreenshotParams;
-use futures::StreamExt;
+use chromiumoxide::Page;
+use futures::stream::{self, Stream, StreamExt};
 use anyhow::{Result, anyhow};
-use crate::models::{CrawlResult, MediaItem, Link, CrawlerRunConfig, WaitStrategy, ExtractionStrategyConfig};
+use crate::models::{BrowserConfig, CrawlerStrategy, DispatcherConfig, CrawlResult, MediaItem, Link, CrawlerRunConfig, WaitStrategy, ExtractionStrategyConfig, MarkdownSource, Redirect};
 use crate::markdown::DefaultMarkdownGenerator;
 use crate::content_filter::{PruningContentFilter, ContentFilter};
+use crate::content_scraping;
+use crate::metadata;
+use crate::cache::CrawlCache;
+use crate::crawler_strategy::{self, site_client, HttpCrawlerStrategy};
+use crate::deep_crawl::{DeepCrawl, DeepCrawlConfig};
+use crate::deep_crawl::filters::FilterRequests;
+use crate::dispatcher::Dispatcher;
+use crate::proxy_strategy::{ProxyConfig, ProxyRotationStrategy};
+use crate::rate_limiter::{RateLimiter, parse_retry_after};
+use crate::request_interception::{PageInterception, RequestInterceptor};
+use crate::robots::{RobotsChecker, DEFAULT_ROBOTS_USER_AGENT};
+use crate::storage_state::{self, Cookie, OriginState, StorageState};
 use std::env;
-use std::path::Path;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Weak};
+use tokio::sync::Mutex;
 use std::time::{Duration, Instant};
 use serde::Deserialize;
 use thiserror::Error;
@@ -34,17 +54,78 @@ pub enum CrawlerError {
     /// HTTP Status Code Error
     #[error("HTTP Error: {0}")]
     HttpStatusCode(i64),
+    /// The URL is disallowed by the site's robots.txt.
+    #[error("Disallowed by robots.txt: {0}")]
+    RobotsDisallowed(String),
     /// Other miscellaneous errors.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
 /// An asynchronous web crawler based on `chromiumoxide`.
-#[derive(Default)]
+///
+/// The crawler is `Send + Sync` and all crawl methods take `&self`, so a single
+/// instance can be wrapped in an `Arc` and shared by many tasks. All of them
+/// use the same browser process, which is started lazily on the first crawl
+/// and restarted transparently if it crashes.
+///
+/// With [`CrawlerStrategy::Http`] pages are fetched over plain HTTP instead and
+/// no browser is ever launched.
 pub struct AsyncWebCrawler {
-    browser: Option<Browser>,
-    handle: Option<tokio::task::JoinHandle<()>>,
-    sessions: HashMap<String, BrowserContextId>,
+    config: BrowserConfig,
+    browser: Mutex<Option<BrowserInstance>>,
+    sessions: Mutex<HashMap<String, Session>>,
+    session_ttl: Duration,
+    rate_limiter: Option<RateLimiter>,
+    request_interceptor: Option<Arc<dyn RequestInterceptor>>,
+    proxy_rotation: Option<ProxyRotationStrategy>,
+    proxy_credentials: ProxyCredentials,
+    /// Browser whose proxy authentication challenges are being answered.
+    proxy_auth: Mutex<Weak<Browser>>,
+    robots: Arc<RobotsChecker>,
+    cache: CrawlCache,
+    /// How pages are fetched, as configured; `http` is built from it.
+    strategy: CrawlerStrategy,
+    http: Option<HttpCrawlerStrategy>,
+}
+
+/// How long a session may sit unused before it is closed (30 minutes).
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// A browser context created for a `session_id`, with the page its crawls run on.
+///
+/// The page stays open between crawls, so later crawls in the session keep its cookies,
+/// scroll position and DOM changes. Its mutex is held for the whole of a crawl, so crawls
+/// in one session never interleave.
+#[derive(Clone)]
+struct Session {
+    context_id: BrowserContextId,
+    page: Arc<Mutex<Option<Page>>>,
+    /// When the last crawl in the session started.
+    last_used: Instant,
+    /// Proxy the context was created with, if it has its own.
+    proxy: Option<ProxyConfig>,
+}
+
+/// Username and password of each proxy in use, by proxy origin.
+type ProxyCredentials = Arc<std::sync::Mutex<HashMap<String, (String, String)>>>;
+
+impl Default for AsyncWebCrawler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A running browser process together with the task driving its CDP handler.
+struct BrowserInstance {
+    browser: Arc<Browser>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl BrowserInstance {
+    fn is_alive(&self) -> bool {
+        !self.handle.is_finished()
+    }
 }
 
 #[derive(Deserialize)]
@@ -56,54 +137,225 @@ struct ExtractionResult {
 impl AsyncWebCrawler {
     /// Creates a new instance of `AsyncWebCrawler`.
     pub fn new() -> Self {
+        Self::with_config(BrowserConfig::default())
+    }
+
+    /// Creates a new instance of `AsyncWebCrawler` that launches the browser with `config`.
+    pub fn with_config(config: BrowserConfig) -> Self {
         Self {
-            browser: None,
-            handle: None,
-            sessions: HashMap::new(),
+            config,
+            browser: Mutex::new(None),
+            sessions: Mutex::new(HashMap::new()),
+            session_ttl: DEFAULT_SESSION_TTL,
+            rate_limiter: None,
+            request_interceptor: None,
+            proxy_rotation: None,
+            proxy_credentials: ProxyCredentials::default(),
+            proxy_auth: Mutex::new(Weak::new()),
+            robots: Arc::new(RobotsChecker::default()),
+            cache: CrawlCache::default(),
+            strategy: CrawlerStrategy::Browser,
+            http: None,
         }
     }
 
-    /// Starts the browser instance.
-    pub async fn start(&mut self) -> Result<()> {
-        if self.browser.is_some() {
-            if let Some(h) = &self.handle {
-                if !h.is_finished() {
-                    return Ok(());
-                }
+    /// Fetches pages with `strategy` instead of the browser.
+    ///
+    /// The HTTP strategy uses the user agent and locale of the browser config, so call
+    /// this after [`AsyncWebCrawler::with_config`].
+    pub fn with_strategy(mut self, strategy: CrawlerStrategy) -> Self {
+        self.http = match strategy {
+            CrawlerStrategy::Browser => None,
+            CrawlerStrategy::Http(ref config) => Some(HttpCrawlerStrategy::new(config, &self.config)),
+        };
+        self.strategy = strategy;
+        self
+    }
+
+    /// Stores cached results in `cache` instead of the default cache directory.
+    pub fn with_cache(mut self, cache: CrawlCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Returns the result cache used by this crawler.
+    pub fn cache(&self) -> &CrawlCache {
+        &self.cache
+    }
+
+    /// Closes sessions that have not been used for `ttl` (default: [`DEFAULT_SESSION_TTL`]).
+    pub fn with_session_ttl(mut self, ttl: Duration) -> Self {
+        self.session_ttl = ttl;
+        self
+    }
+
+    /// Lets `interceptor` continue, abort, modify or fulfill every request of the pages
+    /// crawled on the browser, e.g. to serve fixtures instead of the network.
+    pub fn with_request_interceptor(mut self, interceptor: impl RequestInterceptor + 'static) -> Self {
+        self.request_interceptor = Some(Arc::new(interceptor));
+        self
+    }
+
+    /// Spreads crawls over the proxies of `rotation`. A run's own `proxy` takes precedence.
+    pub fn with_proxy_rotation(mut self, rotation: ProxyRotationStrategy) -> Self {
+        self.proxy_rotation = Some(rotation);
+        self
+    }
+
+    /// Returns the proxy rotation of this crawler, e.g. to run its health checks.
+    pub fn proxy_rotation(&self) -> Option<&ProxyRotationStrategy> {
+        self.proxy_rotation.as_ref()
+    }
+
+    /// Throttles every crawl of this crawler, including batch crawls, with `rate_limiter`.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Returns the browser configuration used by this crawler.
+    pub fn config(&self) -> &BrowserConfig {
+        &self.config
+    }
+
+    /// Starts the browser instance if it is not already running.
+    pub async fn start(&self) -> Result<()> {
+        self.browser().await.map(|_| ())
+    }
+
+    /// Closes the browser instance and drops all sessions.
+    pub async fn close(&self) -> Result<()> {
+        let instance = self.browser.lock().await.take();
+        self.sessions.lock().await.clear();
+
+        if let Some(instance) = instance {
+            // Pages still being crawled by other tasks keep their handle to the browser;
+            // in that case it is killed once the last of them drops it.
+            if let Ok(mut browser) = Arc::try_unwrap(instance.browser) {
+                browser.close().await?;
+                let _ = browser.wait().await;
             }
-            self.browser = None;
-            self.handle = None;
-            self.sessions.clear();
+            instance.handle.abort();
         }
+        Ok(())
+    }
 
-        let mut builder = BrowserConfig::builder();
+    /// Returns the IDs of the open sessions, sorted.
+    pub async fn list_sessions(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.sessions.lock().await.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
 
-        if let Ok(path) = env::var("CHROME_EXECUTABLE") {
-            builder = builder.chrome_executable(Path::new(&path));
-        } else {
-             let known_paths = [
-                 "/usr/bin/google-chrome-stable",
-                 "/usr/bin/chromium",
-                 "/usr/bin/chromium-browser",
-                 "/home/jules/.cache/ms-playwright/chromium-1187/chrome-linux/chrome"
-             ];
-
-             for path_str in known_paths {
-                 let path = Path::new(path_str);
-                 if path.exists() {
-                     builder = builder.chrome_executable(path);
-                     break;
-                 }
-             }
+    /// Closes the page and browser context of `session_id`. Returns whether the session existed.
+    ///
+    /// A crawl in progress on the session is allowed to finish first.
+    pub async fn kill_session(&self, session_id: &str) -> bool {
+        let session = self.sessions.lock().await.remove(session_id);
+        match session {
+            Some(session) => {
+                self.dispose_session(session).await;
+                true
+            }
+            None => false,
         }
+    }
 
-        let config = builder
-            .arg("--no-sandbox")
-            .arg("--disable-dev-shm-usage")
-            .arg("--disable-gpu")
-            .arg("--disable-setuid-sandbox")
-            .build()
-            .map_err(|e| anyhow!(e))?;
+    /// Closes the sessions that have not been used for longer than the session TTL and
+    /// returns how many were closed.
+    ///
+    /// Sessions with a crawl in progress are kept. This also runs before every crawl that
+    /// uses a session.
+    pub async fn close_idle_sessions(&self) -> usize {
+        let expired: Vec<Session> = {
+            let mut sessions = self.sessions.lock().await;
+            let ids: Vec<String> = sessions
+                .iter()
+                .filter(|(_, s)| s.last_used.elapsed() > self.session_ttl && s.page.try_lock().is_ok())
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.iter().filter_map(|id| sessions.remove(id)).collect()
+        };
+
+        let count = expired.len();
+        for session in expired {
+            self.dispose_session(session).await;
+        }
+        count
+    }
+
+    /// Returns the cookies and web storage of `session_id` in Playwright's format, to be
+    /// saved with [`StorageState::save`] and reused as `BrowserConfig::storage_state`.
+    ///
+    /// Web storage can only be read from the origin the session's page is on. Other
+    /// origins of the configured storage state are carried over unchanged.
+    pub async fn export_storage_state(&self, session_id: &str) -> Result<StorageState> {
+        let session = self
+            .sessions
+            .lock()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| CrawlerError::BrowserError(format!("Unknown session: {}", session_id)))?;
+        let browser = self
+            .browser
+            .lock()
+            .await
+            .as_ref()
+            .map(|i| i.browser.clone())
+            .ok_or_else(|| CrawlerError::BrowserError("Browser is not running".to_string()))?;
+
+        let cookies = browser
+            .execute(GetCookiesParams { browser_context_id: Some(session.context_id.clone()) })
+            .await
+            .map_err(|e| CrawlerError::BrowserError(format!("Failed to read cookies: {}", e)))?
+            .result
+            .cookies;
+        let mut state = StorageState {
+            cookies: cookies.iter().map(Cookie::from_cdp).collect(),
+            origins: self.config.storage_state.as_ref().map(|s| s.origins.clone()).unwrap_or_default(),
+        };
+
+        let page = session.page.lock().await.clone();
+        if let Some(page) = page {
+            let origin: OriginState = page.evaluate(storage_state::export_script()).await?.into_value()?;
+            // Pages that never navigated have an opaque origin without storage
+            if origin.origin != "null" {
+                state.set_origin(origin);
+            }
+        }
+        Ok(state)
+    }
+
+    /// Closes the page of a session removed from `sessions` and disposes of its browser context.
+    async fn dispose_session(&self, session: Session) {
+        if let Some(page) = session.page.lock().await.take() {
+            if let Err(e) = page.close().await {
+                eprintln!("Failed to close session page: {}", e);
+            }
+        }
+        let browser = self.browser.lock().await.as_ref().map(|i| i.browser.clone());
+        if let Some(browser) = browser {
+            if let Err(e) = browser.dispose_browser_context(session.context_id).await {
+                eprintln!("Failed to dispose session context: {}", e);
+            }
+        }
+    }
+
+    /// Returns the running browser, launching a new one if there is none or the previous one died.
+    async fn browser(&self) -> Result<Arc<Browser>> {
+        let mut slot = self.browser.lock().await;
+
+        if let Some(instance) = slot.as_ref() {
+            if instance.is_alive() {
+                return Ok(instance.browser.clone());
+            }
+        }
+
+        *slot = None;
+        self.sessions.lock().await.clear();
+
+        let config = Self::build_chrome_config(&self.config)?;
 
         let (browser, mut handler) = Browser::launch(config).await?;
 
@@ -117,42 +369,162 @@ impl AsyncWebCrawler {
             eprintln!("Browser handler loop exited");
         });
 
-        self.browser = Some(browser);
-        self.handle = Some(handle);
+        let browser = Arc::new(browser);
 
-        Ok(())
+        // Pages outside of sessions use the default context
+        let mut setup = self.set_cookies(&browser, None).await;
+        let proxy_auth = self.config.proxy.as_ref().map(|p| self.register_proxy_credentials(p)).unwrap_or(false);
+        if setup.is_ok() && proxy_auth {
+            setup = Self::handle_proxy_auth(&browser, self.proxy_credentials.clone()).await;
+        }
+        if let Err(e) = setup {
+            handle.abort();
+            return Err(e);
+        }
+        if proxy_auth {
+            *self.proxy_auth.lock().await = Arc::downgrade(&browser);
+        }
+        *slot = Some(BrowserInstance { browser: browser.clone(), handle });
+
+        Ok(browser)
+    }
+
+    /// Translates a `BrowserConfig` into the launch configuration used by `chromiumoxide`.
+    fn build_chrome_config(config: &BrowserConfig) -> Result<ChromeConfig> {
+        let mut builder = ChromeConfig::builder()
+            .window_size(config.viewport_width, config.viewport_height)
+            .viewport(Viewport {
+                width: config.viewport_width,
+                height: config.viewport_height,
+                ..Default::default()
+            });
+
+        if let Some(ref path) = config.executable_path {
+            builder = builder.chrome_executable(path);
+        } else if let Ok(path) = env::var("CHROME_EXECUTABLE") {
+            builder = builder.chrome_executable(path);
+        }
+
+        if !config.headless {
+            builder = builder.with_head();
+        } else {
+            builder = builder.arg("--disable-gpu");
+        }
+
+        if !config.sandbox {
+            builder = builder.no_sandbox();
+        }
+
+        if let Some(ref dir) = config.user_data_dir {
+            builder = builder.user_data_dir(dir);
+        }
+
+        if let Some(ref user_agent) = config.user_agent {
+            builder = builder.arg(format!("--user-agent={}", user_agent));
+        }
+
+        if let Some(ref locale) = config.locale {
+            builder = builder
+                .arg(format!("--lang={}", locale))
+                .env("LANGUAGE", locale.replace('-', "_"));
+        }
+
+        if let Some(ref timezone) = config.timezone {
+            builder = builder.env("TZ", timezone.as_str());
+        }
+
+        if let Some(ref proxy) = config.proxy {
+            builder = builder.arg(format!("--proxy-server={}", proxy.server));
+        }
+
+        builder
+            .args(config.extra_args.iter())
+            .build()
+            .map_err(|e| anyhow!(e))
     }
 
     /// Asynchronously crawls a URL with the given configuration.
-    pub async fn arun(&mut self, url: &str, config: Option<CrawlerRunConfig>) -> Result<CrawlResult> {
+    ///
+    /// Besides web pages, `url` may be `raw:<html>` or a `file://` URL. Those are
+    /// processed directly without the browser, cache, robots.txt or rate limiting.
+    pub async fn arun(&self, url: &str, config: Option<CrawlerRunConfig>) -> Result<CrawlResult> {
+        if let Some(result) = crawler_strategy::crawl_local(url).await? {
+            return Ok(Self::process_html(result, &config).await);
+        }
+
+        let cache_mode = config.as_ref().map(|c| c.cache_mode).unwrap_or_default();
+        // js_only runs depend on the state of the session's page, not just the URL
+        let js_only = config.as_ref().map(|c| c.js_only).unwrap_or(false);
+        let cache_key = (CrawlCache::is_cacheable(url) && !js_only).then(|| CrawlCache::cache_key(url, &config, &self.config, &self.strategy));
+
+        if let Some(ref key) = cache_key {
+            if cache_mode.should_read() {
+                if let Some(cached) = self.cache.get(key).await {
+                    // Re-run markdown generation and extraction with this run's config
+                    return Ok(Self::process_html(cached, &config).await);
+                }
+            }
+        }
+
+        if config.as_ref().map(|c| c.check_robots_txt).unwrap_or(false) {
+            let user_agent = self.config.user_agent.as_deref().unwrap_or(DEFAULT_ROBOTS_USER_AGENT);
+            if !self.robots.can_fetch(url, user_agent).await {
+                return Err(CrawlerError::RobotsDisallowed(url.to_string()).into());
+            }
+            self.robots.wait_for_crawl_delay(url, user_agent).await;
+        }
+
+        let result = match self.http {
+            Some(ref http) => self.crawl_http(http, url, &config).await?,
+            None => self.crawl_with_retry(url, &config).await?,
+        };
+
+        if let Some(ref key) = cache_key {
+            if cache_mode.should_write() {
+                if let Err(e) = self.cache.set(key, &result).await {
+                    eprintln!("Failed to write cache entry for {}: {}", url, e);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Crawls `url` on the browser, retrying transient failures and restarting the browser if it crashed.
+    async fn crawl_with_retry(&self, url: &str, config: &Option<CrawlerRunConfig>) -> Result<CrawlResult> {
         let max_retries = 3;
         let base_delay = 500;
+        let init_script = self.config.storage_state.as_ref().and_then(|s| s.init_script());
 
         let mut attempt = 0;
+        // Other crawls of the domain succeeding reset its failure count, so this call also
+        // counts its own rate-limited attempts.
+        let mut rate_limited = 0;
 
         loop {
             attempt += 1;
 
             // 1. Ensure browser is ready
-            if let Err(e) = self.ensure_browser_ready(attempt).await {
-                if attempt >= max_retries { return Err(e); }
-                tokio::time::sleep(Duration::from_millis(base_delay * attempt as u64)).await;
-                continue;
-            }
-
-            // 2. Prepare session
-            // We use a scope here to limit the lifetime of the browser borrow if possible
-            let context_id_result = {
-                let browser = self.browser.as_ref().unwrap();
-                Self::prepare_session(browser, &mut self.sessions, &config).await
+            let browser = match self.ensure_browser_ready(attempt).await {
+                Ok(browser) => browser,
+                Err(e) => {
+                    if attempt >= max_retries { return Err(e); }
+                    tokio::time::sleep(Duration::from_millis(base_delay * attempt as u64)).await;
+                    continue;
+                }
             };
 
-            let context_id = match context_id_result {
+            // 2. Prepare session
+            let session = match self.prepare_session(&browser, config).await {
                 Ok(id) => id,
+                // Retrying does not bring proxies back into the rotation
+                Err(e) if matches!(e.downcast_ref::<CrawlerError>(), Some(CrawlerError::NavigationError(_))) => {
+                    return Err(e);
+                }
                 Err(e) => {
                      let err_str = e.to_string();
                      if Self::is_fatal_error(&err_str) {
-                         self.reset_browser();
+                         self.reset_browser(&browser).await;
                      }
                      if attempt >= max_retries {
                          return Err(e);
@@ -164,20 +536,47 @@ impl AsyncWebCrawler {
             };
 
             // 3. Execute Crawl
-            // We need to re-borrow browser here. This is fine because the previous borrow ended after the block?
-            // Wait, context_id_result borrow? No, context_id is Option<BrowserContextId> which is just a String wrapper usually.
-            // Let's check if BrowserContextId borrows anything. It shouldn't.
-
-            let browser = self.browser.as_ref().unwrap();
-            let crawl_result = Self::crawl_page(browser, context_id, url, &config).await;
+            if let Some(ref limiter) = self.rate_limiter {
+                limiter.wait_if_needed(url).await;
+            }
+            let proxy = session.as_ref().and_then(|s| s.proxy.clone());
+            let temporary = session.clone().filter(|_| config.as_ref().and_then(|c| c.session_id.as_ref()).is_none());
+            let crawl_result = Self::crawl_page(
+                &browser,
+                session,
+                url,
+                config,
+                self.rate_limiter.as_ref(),
+                self.request_interceptor.as_ref(),
+                init_script.as_deref(),
+            )
+            .await;
+            self.report_proxy(proxy.as_ref(), &crawl_result);
+            if let Some(session) = temporary {
+                self.dispose_session(session).await;
+            }
 
             match crawl_result {
                 Ok(res) => return Ok(res),
                 Err(e) => {
+                    // Rate-limited responses are retried with the limiter's backoff
+                    if let (Some(limiter), Some(CrawlerError::HttpStatusCode(code))) =
+                        (&self.rate_limiter, e.downcast_ref::<CrawlerError>())
+                    {
+                        if limiter.is_rate_limit_code(*code) {
+                            rate_limited += 1;
+                            if limiter.retries_exhausted(url) || rate_limited > limiter.max_retries {
+                                return Err(e);
+                            }
+                            eprintln!("Rate limited with status {} (attempt {}), backing off", code, attempt);
+                            continue;
+                        }
+                    }
+
                     // Check if it's a 404 error
                     if let Some(CrawlerError::HttpStatusCode(code)) = e.downcast_ref::<CrawlerError>() {
                         if *code == 404 {
-                            let retry = if let Some(ref cfg) = config {
+                            let retry = if let Some(cfg) = config {
                                 cfg.retry_404
                             } else {
                                 false
@@ -194,7 +593,7 @@ impl AsyncWebCrawler {
                     if is_fatal || attempt < max_retries {
                          eprintln!("Crawl error (attempt {}/{}): {}", attempt, max_retries, err_str);
                          if is_fatal {
-                             self.reset_browser();
+                             self.reset_browser(&browser).await;
                          }
                          if attempt >= max_retries {
                              return Err(e);
@@ -208,19 +607,179 @@ impl AsyncWebCrawler {
         }
     }
 
-    async fn ensure_browser_ready(&mut self, attempt: u32) -> Result<()> {
-        if self.browser.is_none() || self.handle.as_ref().map(|h| h.is_finished()).unwrap_or(true) {
-            if let Err(e) = self.start().await {
+    /// Fetches `url` with the HTTP strategy, retrying rate-limited responses and transient failures.
+    async fn crawl_http(
+        &self,
+        http: &HttpCrawlerStrategy,
+        url: &str,
+        config: &Option<CrawlerRunConfig>,
+    ) -> Result<CrawlResult> {
+        let max_retries = 3;
+        let base_delay = 500;
+
+        let mut attempt = 0;
+        let mut rate_limited = 0;
+
+        loop {
+            attempt += 1;
+
+            if let Some(ref limiter) = self.rate_limiter {
+                limiter.wait_if_needed(url).await;
+            }
+
+            let proxy = self.select_proxy(config)?;
+            let crawl_result = http.crawl(url, config, self.rate_limiter.as_ref(), proxy.as_ref()).await;
+            self.report_proxy(proxy.as_ref(), &crawl_result);
+            let e = match crawl_result {
+                Ok(result) => return Ok(Self::process_html(result, config).await),
+                Err(e) => e,
+            };
+
+            let status = match e.downcast_ref::<CrawlerError>() {
+                Some(CrawlerError::HttpStatusCode(code)) => Some(*code),
+                _ => None,
+            };
+
+            // Rate-limited responses are retried with the limiter's backoff
+            if let (Some(limiter), Some(code)) = (&self.rate_limiter, status) {
+                if limiter.is_rate_limit_code(code) {
+                    rate_limited += 1;
+                    if limiter.retries_exhausted(url) || rate_limited > limiter.max_retries {
+                        return Err(e);
+                    }
+                    eprintln!("Rate limited with status {} (attempt {}), backing off", code, attempt);
+                    continue;
+                }
+            }
+
+            let retry_404 = config.as_ref().map(|c| c.retry_404).unwrap_or(false);
+            if (status == Some(404) && !retry_404) || attempt >= max_retries {
+                return Err(e);
+            }
+
+            eprintln!("Crawl error (attempt {}/{}): {}", attempt, max_retries, e);
+            tokio::time::sleep(Duration::from_millis(base_delay * attempt as u64)).await;
+        }
+    }
+
+    /// Crawls several URLs concurrently on the shared browser.
+    ///
+    /// Concurrency is governed by `dispatcher` (memory-adaptive by default). Results are
+    /// returned in the same order as `urls`; failed crawls are reported as a `CrawlResult`
+    /// with `success: false` and an `error_message`.
+    pub async fn arun_many<I, S>(
+        &self,
+        urls: I,
+        config: Option<CrawlerRunConfig>,
+        dispatcher: Option<DispatcherConfig>,
+    ) -> Result<Vec<CrawlResult>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut results: Vec<(usize, CrawlResult)> = self
+            .crawl_many(urls, config, dispatcher)
+            .await?
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _)| *index);
+        Ok(results.into_iter().map(|(_, result)| result).collect())
+    }
+
+    /// Crawls several URLs concurrently, yielding each `CrawlResult` as soon as its page finishes.
+    ///
+    /// See [`AsyncWebCrawler::arun_many`] for how concurrency and failures are handled.
+    pub async fn arun_many_stream<'a, I, S>(
+        &'a self,
+        urls: I,
+        config: Option<CrawlerRunConfig>,
+        dispatcher: Option<DispatcherConfig>,
+    ) -> Result<impl Stream<Item = CrawlResult> + 'a>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let results = self.crawl_many(urls, config, dispatcher).await?;
+        Ok(results.map(|(_, result)| result))
+    }
+
+    /// Crawls `url` and the pages it links to, following links as configured by `deep_config`.
+    ///
+    /// Each `CrawlResult` is yielded once its page has been crawled, tagged with its `depth`
+    /// and `parent_url`. Pages are crawled with `config`; failed pages are yielded with
+    /// `success: false` and their links are not followed.
+    pub fn adeep_crawl<'a>(
+        &'a self,
+        url: &str,
+        config: Option<CrawlerRunConfig>,
+        deep_config: DeepCrawlConfig,
+    ) -> impl Stream<Item = CrawlResult> + 'a {
+        DeepCrawl::new(self, url, config, deep_config).into_stream()
+    }
+
+    async fn crawl_many<'a, I, S>(
+        &'a self,
+        urls: I,
+        config: Option<CrawlerRunConfig>,
+        dispatcher: Option<DispatcherConfig>,
+    ) -> Result<impl Stream<Item = (usize, CrawlResult)> + 'a>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let urls: Vec<String> = urls.into_iter().map(Into::into).collect();
+
+        // Launch the browser up front so that a broken setup fails the whole batch
+        // instead of every page individually.
+        if self.http.is_none() && !urls.iter().all(|url| crawler_strategy::is_local_url(url)) {
+            self.ensure_browser_ready(1).await?;
+        }
+
+        let dispatcher = Arc::new(Dispatcher::new(dispatcher.unwrap_or_default()));
+        let max_concurrent = dispatcher.max_concurrent();
+
+        let results = stream::iter(urls.into_iter().enumerate())
+            .map(move |(index, url)| {
+                let dispatcher = dispatcher.clone();
+                let config = config.clone();
+                async move {
+                    let result = match dispatcher.acquire().await {
+                        Ok(_permit) => self.arun(&url, config).await,
+                        Err(e) => Err(e),
+                    };
+                    let result = result.unwrap_or_else(|e| CrawlResult {
+                        url: url.clone(),
+                        success: false,
+                        error_message: Some(e.to_string()),
+                        ..Default::default()
+                    });
+                    (index, result)
+                }
+            })
+            .buffer_unordered(max_concurrent);
+
+        Ok(results)
+    }
+
+    async fn ensure_browser_ready(&self, attempt: u32) -> Result<Arc<Browser>> {
+        match self.browser().await {
+            Ok(browser) => Ok(browser),
+            Err(e) => {
                 eprintln!("Failed to start browser (attempt {}): {}", attempt, e);
-                return Err(CrawlerError::BrowserError(format!("Failed to start browser: {}", e)).into());
+                Err(CrawlerError::BrowserError(format!("Failed to start browser: {}", e)).into())
             }
         }
-        Ok(())
     }
 
-    fn reset_browser(&mut self) {
-        self.browser = None;
-        self.sessions.clear();
+    /// Drops the given browser so that the next crawl launches a fresh one.
+    ///
+    /// Does nothing if another task has already replaced it.
+    async fn reset_browser(&self, failed: &Arc<Browser>) {
+        let mut slot = self.browser.lock().await;
+        if slot.as_ref().map(|i| Arc::ptr_eq(&i.browser, failed)).unwrap_or(false) {
+            *slot = None;
+            self.sessions.lock().await.clear();
+        }
     }
 
     fn is_fatal_error(err_str: &str) -> bool {
@@ -230,297 +789,658 @@ impl AsyncWebCrawler {
         err_str.contains("Connection reset by peer")
     }
 
-    async fn prepare_session(
-        browser: &Browser,
-        sessions: &mut HashMap<String, BrowserContextId>,
-        config: &Option<CrawlerRunConfig>
-    ) -> Result<Option<BrowserContextId>> {
+    /// Looks up a response header by case-insensitive name.
+    fn header_value(headers: &network::Headers, name: &str) -> Option<String> {
+        headers.inner().as_object()?.iter().find_map(|(key, value)| {
+            if key.eq_ignore_ascii_case(name) {
+                value.as_str().map(|v| v.to_string())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Runs `scripts` on `page` in order and collects their return values.
+    ///
+    /// Returned promises are awaited. A script that throws is recorded as
+    /// `{"error": message}` and the remaining scripts still run.
+    async fn execute_js(page: &Page, scripts: &[String]) -> Vec<serde_json::Value> {
+        let mut results = Vec::with_capacity(scripts.len());
+        for script in scripts {
+            let params = EvaluateParams::builder()
+                .expression(script.as_str())
+                .await_promise(true)
+                .return_by_value(true)
+                .eval_as_function_fallback(true)
+                .build()
+                .map_err(|e| anyhow!(e));
+            let result = match params {
+                Ok(params) => page.evaluate(params).await.map_err(anyhow::Error::from),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(value) => results.push(value.value().cloned().unwrap_or(serde_json::Value::Null)),
+                Err(e) => {
+                    eprintln!("JavaScript execution failed: {}", e);
+                    results.push(serde_json::json!({ "error": e.to_string() }));
+                }
+            }
+        }
+        results
+    }
+
+    /// Collects response headers into a map keyed by lowercased name.
+    fn headers_map(headers: &network::Headers) -> HashMap<String, String> {
+        headers
+            .inner()
+            .as_object()
+            .map(|object| {
+                object
+                    .iter()
+                    .filter_map(|(key, value)| value.as_str().map(|v| (key.to_lowercase(), v.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sets the cookies of the browser config and its storage state in a browser context,
+    /// or in the default context if `context_id` is `None`.
+    async fn set_cookies(&self, browser: &Browser, context_id: Option<BrowserContextId>) -> Result<()> {
+        let cookies: Vec<_> = self
+            .config
+            .cookies
+            .iter()
+            .chain(self.config.storage_state.iter().flat_map(|state| &state.cookies))
+            .map(Cookie::to_cdp)
+            .collect();
+        if cookies.is_empty() {
+            return Ok(());
+        }
+        browser
+            .execute(SetCookiesParams { cookies, browser_context_id: context_id })
+            .await
+            .map_err(|e| CrawlerError::BrowserError(format!("Failed to set cookies: {}", e)))?;
+        Ok(())
+    }
+
+    /// Returns the session of the run, creating it with the proxy from `select_proxy` if it
+    /// does not exist yet.
+    ///
+    /// Runs without a session that use a proxy get a temporary session of their own, to be
+    /// disposed of after the crawl.
+    async fn prepare_session(&self, browser: &Arc<Browser>, config: &Option<CrawlerRunConfig>) -> Result<Option<Session>> {
         if let Some(ref cfg) = config {
             if let Some(ref session_id) = cfg.session_id {
-                 if let Some(id) = sessions.get(session_id) {
-                     return Ok(Some(id.clone()));
+                 self.close_idle_sessions().await;
+
+                 // Hold the lock while creating the context so that concurrent
+                 // requests for the same new session share a single context.
+                 let mut sessions = self.sessions.lock().await;
+                 if let Some(session) = sessions.get_mut(session_id) {
+                     session.last_used = Instant::now();
+                     return Ok(Some(session.clone()));
                  } else {
-                     let id = browser.create_browser_context(CreateBrowserContextParams::default()).await
-                        .map_err(|e| CrawlerError::BrowserError(format!("Failed to create session: {}", e)))?;
-                     sessions.insert(session_id.clone(), id.clone());
-                     return Ok(Some(id));
+                     // An existing session keeps the proxy it was created with, so one is
+                     // only picked for a new session
+                     let proxy = self.select_proxy(config)?;
+                     let session = self.create_session(browser, proxy.as_ref()).await?;
+                     sessions.insert(session_id.clone(), session.clone());
+                     return Ok(Some(session));
                  }
             }
         }
-        Ok(None)
+        match self.select_proxy(config)? {
+            Some(proxy) => Ok(Some(self.create_session(browser, Some(&proxy)).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Creates a browser context, using `proxy` if given, with the configured cookies set.
+    async fn create_session(&self, browser: &Arc<Browser>, proxy: Option<&ProxyConfig>) -> Result<Session> {
+        let mut params = CreateBrowserContextParams::default();
+        if let Some(proxy) = proxy {
+            params.proxy_server = Some(proxy.server.clone());
+            if self.register_proxy_credentials(proxy) {
+                self.enable_proxy_auth(browser).await?;
+            }
+        }
+        let id = browser.create_browser_context(params).await
+            .map_err(|e| CrawlerError::BrowserError(format!("Failed to create session: {}", e)))?;
+        self.set_cookies(browser, Some(id.clone())).await?;
+        Ok(Session {
+            context_id: id,
+            page: Arc::new(Mutex::new(None)),
+            last_used: Instant::now(),
+            proxy: proxy.cloned(),
+        })
+    }
+
+    /// Remembers the credentials of `proxy` for its authentication challenges. Returns
+    /// whether it has any.
+    fn register_proxy_credentials(&self, proxy: &ProxyConfig) -> bool {
+        let (Some(credentials), Some(origin)) = (proxy.credentials(), proxy.origin()) else { return false };
+        if proxy.server.starts_with("socks") {
+            eprintln!("Chrome cannot authenticate to SOCKS proxies; ignoring the credentials of {}", proxy.server);
+            return false;
+        }
+        self.proxy_credentials.lock().unwrap().insert(origin, credentials);
+        true
+    }
+
+    /// Starts answering the proxy authentication challenges of `browser` unless it already does.
+    async fn enable_proxy_auth(&self, browser: &Arc<Browser>) -> Result<()> {
+        let mut current = self.proxy_auth.lock().await;
+        if current.upgrade().map(|b| Arc::ptr_eq(&b, browser)).unwrap_or(false) {
+            return Ok(());
+        }
+        Self::handle_proxy_auth(browser, self.proxy_credentials.clone()).await?;
+        *current = Arc::downgrade(browser);
+        Ok(())
+    }
+
+    /// Answers the proxy authentication challenges of `browser` (CDP `Fetch.authRequired`)
+    /// with the credentials registered for the proxy's origin.
+    ///
+    /// Requests are intercepted on the browser target rather than on each page, because
+    /// chromiumoxide answers page-level challenges itself. Paused requests are continued
+    /// unchanged.
+    async fn handle_proxy_auth(browser: &Arc<Browser>, credentials: ProxyCredentials) -> Result<()> {
+        let mut paused = browser.event_listener::<fetch::EventRequestPaused>().await?;
+        let mut auth_required = browser.event_listener::<fetch::EventAuthRequired>().await?;
+        let params = fetch::EnableParams::builder()
+            .handle_auth_requests(true)
+            .pattern(RequestPattern::builder().url_pattern("*").build())
+            .build();
+        browser.execute(params).await
+            .map_err(|e| CrawlerError::BrowserError(format!("Failed to enable proxy authentication: {}", e)))?;
+
+        let browser = Arc::downgrade(browser);
+        tokio::spawn(async move {
+            let mut attempted = HashSet::new();
+            loop {
+                tokio::select! {
+                    Some(event) = paused.next() => {
+                        let Some(browser) = browser.upgrade() else { break };
+                        let request_id = event.request_id.clone();
+                        tokio::spawn(async move {
+                            let _ = browser.execute(fetch::ContinueRequestParams::new(request_id)).await;
+                        });
+                    }
+                    Some(event) = auth_required.next() => {
+                        let Some(browser) = browser.upgrade() else { break };
+                        let challenge = &event.auth_challenge;
+                        let found = if challenge.source == Some(AuthChallengeSource::Proxy) {
+                            credentials.lock().unwrap().get(&challenge.origin).cloned()
+                        } else {
+                            None
+                        };
+                        let response = match found {
+                            // A second challenge for the same request means the credentials were rejected
+                            Some(_) if !attempted.insert(event.request_id.inner().clone()) => {
+                                AuthChallengeResponse::new(AuthChallengeResponseResponse::CancelAuth)
+                            }
+                            Some((username, password)) => {
+                                let mut response = AuthChallengeResponse::new(AuthChallengeResponseResponse::ProvideCredentials);
+                                response.username = Some(username);
+                                response.password = Some(password);
+                                response
+                            }
+                            None => AuthChallengeResponse::new(AuthChallengeResponseResponse::Default),
+                        };
+                        let request_id = event.request_id.clone();
+                        tokio::spawn(async move {
+                            let _ = browser.execute(fetch::ContinueWithAuthParams::new(request_id, response)).await;
+                        });
+                    }
+                    else => break,
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Returns how the deep crawl filters of a run send their requests: with this crawler's
+    /// user agent, through the run's proxy (or the browser's), after its rate limiter, and only
+    /// where `robots.txt` allows it when the run checks it.
+    pub(crate) fn filter_requests(&self, config: &Option<CrawlerRunConfig>) -> FilterRequests {
+        let proxy = config.as_ref().and_then(|c| c.proxy.as_ref()).or(self.config.proxy.as_ref());
+        let client = site_client(&self.config, proxy).unwrap_or_else(|e| {
+            eprintln!("Failed to build filter HTTP client, using defaults: {}", e);
+            reqwest::Client::new()
+        });
+        let robots = config.as_ref().filter(|c| c.check_robots_txt).map(|_| {
+            let user_agent = self.config.user_agent.as_deref().unwrap_or(DEFAULT_ROBOTS_USER_AGENT);
+            (self.robots.clone(), user_agent.to_string())
+        });
+        FilterRequests {
+            client,
+            rate_limiter: self.rate_limiter.clone(),
+            robots,
+        }
+    }
+
+    /// Returns the proxy for the next attempt at a crawl: the run's own proxy, or the next
+    /// one of the rotation.
+    fn select_proxy(&self, config: &Option<CrawlerRunConfig>) -> Result<Option<ProxyConfig>> {
+        if let Some(proxy) = config.as_ref().and_then(|c| c.proxy.clone()) {
+            return Ok(Some(proxy));
+        }
+        let Some(ref rotation) = self.proxy_rotation else { return Ok(None) };
+        let session_id = config.as_ref().and_then(|c| c.session_id.as_deref());
+        match rotation.next_proxy(session_id) {
+            Some(proxy) => Ok(Some(proxy)),
+            None => Err(CrawlerError::NavigationError("No healthy proxy left in the rotation".to_string()).into()),
+        }
+    }
+
+    /// Reports the outcome of a crawl through `proxy` to the rotation.
+    fn report_proxy(&self, proxy: Option<&ProxyConfig>, result: &Result<CrawlResult>) {
+        let (Some(rotation), Some(proxy)) = (&self.proxy_rotation, proxy) else { return };
+        match result.as_ref().map_err(|e| e.downcast_ref::<CrawlerError>()) {
+            Ok(_) => rotation.report_success(proxy),
+            // Error statuses come from the site, unless the proxy rejected our credentials
+            Err(Some(CrawlerError::HttpStatusCode(code))) if *code != 407 => {}
+            Err(_) => {
+                rotation.report_failure(proxy);
+            }
+        }
     }
 
     /// Internal method to perform the actual page visit and extraction.
     async fn crawl_page(
         browser: &Browser,
-        context_id: Option<BrowserContextId>,
+        session: Option<Session>,
         url: &str,
-        config: &Option<CrawlerRunConfig>
+        config: &Option<CrawlerRunConfig>,
+        rate_limiter: Option<&RateLimiter>,
+        interceptor: Option<&Arc<dyn RequestInterceptor>>,
+        init_script: Option<&str>,
     ) -> Result<CrawlResult> {
-        let page = if let Some(cid) = context_id {
-            let params = CreateTargetParams::builder()
-                .url("about:blank")
-                .browser_context_id(cid)
-                .build()
-                .map_err(|e| anyhow!(e))?;
-            browser.new_page(params).await?
-        } else {
-            browser.new_page("about:blank").await?
-        };
-
-        let response_task = page.wait_for_navigation_response();
+        let js_only = config.as_ref().map(|c| c.js_only).unwrap_or(false);
 
-        let goto_result = if let Some(timeout_ms) = config.as_ref().and_then(|c| c.page_timeout) {
-             match tokio::time::timeout(Duration::from_millis(timeout_ms), page.goto(url)).await {
-                 Ok(res) => res.map(|_| ()).map_err(|e| e.into()),
-                 Err(_) => Err(CrawlerError::Timeout("Page navigation timed out".to_string()).into()),
-             }
-        } else {
-             page.goto(url).await.map(|_| ()).map_err(|e| e.into())
+        let mut session_page = match session {
+            Some(ref session) => Some(session.page.clone().lock_owned().await),
+            None => None,
         };
-
-        // Attempt to get the response regardless of goto success
-        // Use a timeout to prevent infinite hang if goto timed out/failed and no response came.
-        let response = match tokio::time::timeout(Duration::from_secs(2), response_task).await {
-            Ok(res) => res,
-            Err(_) => {
-                // If response task times out, we rely on goto_result for error
-                Ok(None)
+        let page = match session_page.as_ref().and_then(|slot| (**slot).clone()) {
+            Some(page) => page,
+            None if js_only => {
+                return Err(CrawlerError::NavigationError("js_only needs a session with an open page".to_string()).into());
             }
-        };
-
-        // Check response first for status codes
-        if let Ok(Some(req)) = response {
-            if let Some(resp) = req.response.as_ref() {
-                if resp.status == 404 {
-                    return Err(CrawlerError::HttpStatusCode(404).into());
+            None => {
+                let page = if let Some(ref session) = session {
+                    let params = CreateTargetParams::builder()
+                        .url("about:blank")
+                        .browser_context_id(session.context_id.clone())
+                        .build()
+                        .map_err(|e| anyhow!(e))?;
+                    browser.new_page(params).await?
+                } else {
+                    browser.new_page("about:blank").await?
+                };
+                if let Some(script) = init_script {
+                    page.execute(AddScriptToEvaluateOnNewDocumentParams::new(script)).await?;
                 }
-                if resp.status >= 400 {
-                    eprintln!("Page returned status: {}", resp.status);
-                    return Err(CrawlerError::HttpStatusCode(resp.status).into());
+                if let Some(ref mut slot) = session_page {
+                    **slot = Some(page.clone());
                 }
+                page
             }
-        }
+        };
 
-        // If no status error, check if goto failed
-        if let Err(e) = goto_result {
-            return Err(e);
-        }
+        let block = config.as_ref().and_then(|c| c.block_resources.as_ref());
+        let interception = PageInterception::start(&page, block, interceptor.cloned()).await?;
 
-        if let Some(ref cfg) = config {
-            if let Some(ref strategy) = cfg.wait_for {
-                let timeout_ms = cfg.wait_timeout.unwrap_or(10_000);
-                let timeout = Duration::from_millis(timeout_ms);
-                let start = Instant::now();
-
-                match strategy {
-                    WaitStrategy::Fixed(ms) => {
-                        tokio::time::sleep(Duration::from_millis(*ms)).await;
-                    },
-                    WaitStrategy::Selector(selector) => {
-                        loop {
-                            if start.elapsed() > timeout {
-                                eprintln!("Timeout waiting for selector: {}", selector);
-                                break;
-                            }
-                            match page.find_element(selector).await {
-                                Ok(_) => break,
-                                Err(_) => {
-                                    tokio::time::sleep(Duration::from_millis(500)).await;
-                                }
+        let loaded: Result<_> = async {
+            let mut status_code = None;
+            let mut response_headers = None;
+            let mut redirected_url = None;
+            let mut redirect_chain = Vec::new();
+
+            // A js_only run continues on the session's page as it is
+            if !js_only {
+                let response_task = page.wait_for_navigation_response();
+
+                let goto_result: Result<()> = if let Some(timeout_ms) = config.as_ref().and_then(|c| c.page_timeout) {
+                     match tokio::time::timeout(Duration::from_millis(timeout_ms), page.goto(url)).await {
+                         Ok(res) => res.map(|_| ()).map_err(|e| e.into()),
+                         Err(_) => Err(CrawlerError::Timeout("Page navigation timed out".to_string()).into()),
+                     }
+                } else {
+                     page.goto(url).await.map(|_| ()).map_err(|e| e.into())
+                };
+
+                // Attempt to get the response regardless of goto success
+                // Use a timeout to prevent infinite hang if goto timed out/failed and no response came.
+                let response = match tokio::time::timeout(Duration::from_secs(2), response_task).await {
+                    Ok(res) => res,
+                    Err(_) => {
+                        // If response task times out, we rely on goto_result for error
+                        Ok(None)
+                    }
+                };
+
+                // Check response first for status codes
+                if let Ok(Some(req)) = response {
+                    redirect_chain = req
+                        .redirect_chain
+                        .iter()
+                        .filter_map(|hop| hop.response.as_ref())
+                        .map(|resp| Redirect { url: resp.url.clone(), status_code: resp.status as u16 })
+                        .collect();
+                    if let Some(resp) = req.response.as_ref() {
+                        status_code = Some(resp.status as u16);
+                        response_headers = Some(Self::headers_map(&resp.headers));
+                        redirected_url = Some(resp.url.clone());
+                        if let Some(limiter) = rate_limiter {
+                            let retry_after = Self::header_value(&resp.headers, "retry-after")
+                                .and_then(|v| parse_retry_after(&v));
+                            if !limiter.update_delay(url, resp.status, retry_after) {
+                                eprintln!("Rate limit retries exhausted for {}", url);
                             }
                         }
-                    },
-                    WaitStrategy::XPath(xpath) => {
-                         // Escape backslashes first, then quotes to prevent injection issues
-                         let escaped_xpath = xpath.replace("\\", "\\\\").replace("\"", "\\\"");
-                         let js = format!(
-                             r#"
-                             (() => {{
-                                 const result = document.evaluate("{}", document, null, XPathResult.FIRST_ORDERED_NODE_TYPE, null);
-                                 return result.singleNodeValue !== null;
-                             }})()
-                             "#,
-                             escaped_xpath
-                         );
-                         loop {
-                            if start.elapsed() > timeout {
-                                eprintln!("Timeout waiting for xpath: {}", xpath);
-                                break;
-                            }
-                            match page.evaluate(js.as_str()).await {
-                                Ok(val) => {
-                                    if let Ok(true) = val.into_value::<bool>() {
-                                        break;
-                                    }
-                                },
-                                Err(_) => {}
-                            }
-                            tokio::time::sleep(Duration::from_millis(500)).await;
+                        if resp.status == 404 {
+                            return Err(CrawlerError::HttpStatusCode(404).into());
+                        }
+                        if resp.status >= 400 {
+                            eprintln!("Page returned status: {}", resp.status);
+                            return Err(CrawlerError::HttpStatusCode(resp.status).into());
                         }
-                    },
-                    WaitStrategy::JsCondition(js) => {
-                         loop {
-                            if start.elapsed() > timeout {
-                                eprintln!("Timeout waiting for js condition");
-                                break;
+                    }
+                }
+
+                // If no status error, check if goto failed
+                goto_result?;
+            }
+
+            let js_execution_result = match config {
+                Some(ref cfg) if !cfg.js_code.is_empty() => Some(Self::execute_js(&page, &cfg.js_code).await),
+                _ => None,
+            };
+
+            if let Some(ref cfg) = config {
+                if let Some(ref strategy) = cfg.wait_for {
+                    let timeout_ms = cfg.wait_timeout.unwrap_or(10_000);
+                    let timeout = Duration::from_millis(timeout_ms);
+                    let start = Instant::now();
+
+                    match strategy {
+                        WaitStrategy::Fixed(ms) => {
+                            tokio::time::sleep(Duration::from_millis(*ms)).await;
+                        },
+                        WaitStrategy::Selector(selector) => {
+                            loop {
+                                if start.elapsed() > timeout {
+                                    eprintln!("Timeout waiting for selector: {}", selector);
+                                    break;
+                                }
+                                match page.find_element(selector).await {
+                                    Ok(_) => break,
+                                    Err(_) => {
+                                        tokio::time::sleep(Duration::from_millis(500)).await;
+                                    }
+                                }
                             }
-                            match page.evaluate(js.as_str()).await {
-                                Ok(val) => {
+                        },
+                        WaitStrategy::XPath(xpath) => {
+                             // Escape backslashes first, then quotes to prevent injection issues
+                             let escaped_xpath = xpath.replace("\\", "\\\\").replace("\"", "\\\"");
+                             let js = format!(
+                                 r#"
+                                 (() => {{
+                                     const result = document.evaluate("{}", document, null, XPathResult.FIRST_ORDERED_NODE_TYPE, null);
+                                     return result.singleNodeValue !== null;
+                                 }})()
+                                 "#,
+                                 escaped_xpath
+                             );
+                             loop {
+                                if start.elapsed() > timeout {
+                                    eprintln!("Timeout waiting for xpath: {}", xpath);
+                                    break;
+                                }
+                                if let Ok(val) = page.evaluate(js.as_str()).await {
                                     if let Ok(true) = val.into_value::<bool>() {
                                         break;
                                     }
-                                },
-                                Err(_) => {}
+                                }
+                                tokio::time::sleep(Duration::from_millis(500)).await;
                             }
-                            tokio::time::sleep(Duration::from_millis(500)).await;
-                         }
-                    },
-                    WaitStrategy::NetworkIdle { idle_time } => {
-                        if let Err(e) = page.execute(network::EnableParams::default()).await {
-                             eprintln!("Failed to enable network domain for idle wait: {}", e);
-                        } else {
-                            // Note: This implementation only tracks requests initiated AFTER this point.
-                            // In-flight requests started before this block are not counted.
-                            let request_sent = page.event_listener::<EventRequestWillBeSent>().await;
-                            let request_finished = page.event_listener::<EventLoadingFinished>().await;
-                            let request_failed = page.event_listener::<EventLoadingFailed>().await;
-
-                            if let (Ok(mut request_sent), Ok(mut request_finished), Ok(mut request_failed)) =
-                                (request_sent, request_finished, request_failed)
-                            {
-                                let mut active_requests = 0;
-                                let mut last_activity = Instant::now();
-                                let required_idle_time = Duration::from_millis(idle_time.unwrap_or(500));
-                                let start_wait = Instant::now();
-
-                                loop {
-                                    if start_wait.elapsed() > timeout {
-                                        eprintln!("Timeout waiting for network idle");
-                                        break;
-                                    }
-
-                                    if active_requests == 0 && last_activity.elapsed() > required_idle_time {
+                        },
+                        WaitStrategy::JsCondition(js) => {
+                             loop {
+                                if start.elapsed() > timeout {
+                                    eprintln!("Timeout waiting for js condition");
+                                    break;
+                                }
+                                if let Ok(val) = page.evaluate(js.as_str()).await {
+                                    if let Ok(true) = val.into_value::<bool>() {
                                         break;
                                     }
-
-                                    tokio::select! {
-                                        _ = tokio::time::sleep(Duration::from_millis(100)) => {
-                                            // Periodic check
-                                        }
-                                        Some(_) = request_sent.next() => {
-                                            active_requests += 1;
-                                            last_activity = Instant::now();
+                                }
+                                tokio::time::sleep(Duration::from_millis(500)).await;
+                             }
+                        },
+                        WaitStrategy::NetworkIdle { idle_time } => {
+                            if let Err(e) = page.execute(network::EnableParams::default()).await {
+                                 eprintln!("Failed to enable network domain for idle wait: {}", e);
+                            } else {
+                                // Note: This implementation only tracks requests initiated AFTER this point.
+                                // In-flight requests started before this block are not counted.
+                                let request_sent = page.event_listener::<EventRequestWillBeSent>().await;
+                                let request_finished = page.event_listener::<EventLoadingFinished>().await;
+                                let request_failed = page.event_listener::<EventLoadingFailed>().await;
+
+                                if let (Ok(mut request_sent), Ok(mut request_finished), Ok(mut request_failed)) =
+                                    (request_sent, request_finished, request_failed)
+                                {
+                                    let mut active_requests = 0;
+                                    let mut last_activity = Instant::now();
+                                    let required_idle_time = Duration::from_millis(idle_time.unwrap_or(500));
+                                    let start_wait = Instant::now();
+
+                                    loop {
+                                        if start_wait.elapsed() > timeout {
+                                            eprintln!("Timeout waiting for network idle");
+                                            break;
                                         }
-                                        Some(_) = request_finished.next() => {
-                                            if active_requests > 0 { active_requests -= 1; }
-                                            last_activity = Instant::now();
+
+                                        if active_requests == 0 && last_activity.elapsed() > required_idle_time {
+                                            break;
                                         }
-                                        Some(_) = request_failed.next() => {
-                                            if active_requests > 0 { active_requests -= 1; }
-                                            last_activity = Instant::now();
+
+                                        tokio::select! {
+                                            _ = tokio::time::sleep(Duration::from_millis(100)) => {
+                                                // Periodic check
+                                            }
+                                            Some(_) = request_sent.next() => {
+                                                active_requests += 1;
+                                                last_activity = Instant::now();
+                                            }
+                                            Some(_) = request_finished.next() => {
+                                                if active_requests > 0 { active_requests -= 1; }
+                                                last_activity = Instant::now();
+                                            }
+                                            Some(_) = request_failed.next() => {
+                                                if active_requests > 0 { active_requests -= 1; }
+                                                last_activity = Instant::now();
+                                            }
                                         }
                                     }
+                                } else {
+                                    eprintln!("Failed to attach event listeners for network idle");
                                 }
-                            } else {
-                                eprintln!("Failed to attach event listeners for network idle");
                             }
                         }
                     }
                 }
             }
-        }
-
-        let html = page.content().await?;
-
-        let screenshot_data = if let Some(ref cfg) = config {
-            if cfg.screenshot {
-                let params = ScreenshotParams::builder()
-                    .format(CaptureScreenshotFormat::Png)
-                    .full_page(true)
-                    .build();
 
-                match page.screenshot(params).await {
-                    Ok(bytes) => {
-                        use base64::{Engine as _, engine::general_purpose};
-                        Some(general_purpose::STANDARD.encode(bytes))
-                    },
-                    Err(e) => {
-                        eprintln!("Failed to take screenshot: {}", e);
-                        None
+            let html = page.content().await?;
+
+            let screenshot_data = if let Some(ref cfg) = config {
+                if cfg.screenshot {
+                    let params = ScreenshotParams::builder()
+                        .format(CaptureScreenshotFormat::Png)
+                        .full_page(true)
+                        .build();
+
+                    match page.screenshot(params).await {
+                        Ok(bytes) => {
+                            use base64::{Engine as _, engine::general_purpose};
+                            Some(general_purpose::STANDARD.encode(bytes))
+                        },
+                        Err(e) => {
+                            eprintln!("Failed to take screenshot: {}", e);
+                            None
+                        }
                     }
+                } else {
+                    None
                 }
             } else {
                 None
-            }
-        } else {
-            None
-        };
-
-        // Extract media and links using JavaScript
-        let script = r#"
-            (() => {
-                const resolveUrl = (url) => {
-                    try {
-                        return new URL(url, document.baseURI).href;
-                    } catch (e) {
-                        return url;
-                    }
-                };
+            };
 
-                const media = {};
-                const images = Array.from(document.images).map(img => ({
-                    src: resolveUrl(img.src),
-                    alt: img.alt || null,
-                    desc: img.title || null,
-                    score: null,
-                    type: "image",
-                    group_id: null
-                }));
-                media["images"] = images;
-
-                const links = { internal: [], external: [] };
-                const domain = window.location.hostname;
-
-                Array.from(document.links).forEach(link => {
-                    const href = resolveUrl(link.href);
-                    const linkObj = {
-                        href: href,
-                        text: link.innerText || null,
-                        title: link.title || null
+            // Extract media and links using JavaScript
+            let script = r#"
+                (() => {
+                    const resolveUrl = (url) => {
+                        try {
+                            return new URL(url, document.baseURI).href;
+                        } catch (e) {
+                            return url;
+                        }
                     };
 
-                    try {
-                        const linkUrl = new URL(href);
-                        if (linkUrl.hostname && linkUrl.hostname === domain) {
-                            links.internal.push(linkObj);
-                        } else {
+                    const media = {};
+                    const images = Array.from(document.images).map(img => ({
+                        src: resolveUrl(img.src),
+                        alt: img.alt || null,
+                        desc: img.title || null,
+                        score: null,
+                        type: "image",
+                        group_id: null
+                    }));
+                    media["images"] = images;
+
+                    const links = { internal: [], external: [] };
+                    const domain = window.location.hostname;
+
+                    Array.from(document.links).forEach(link => {
+                        const href = resolveUrl(link.href);
+                        const linkObj = {
+                            href: href,
+                            text: link.innerText || null,
+                            title: link.title || null
+                        };
+
+                        try {
+                            const linkUrl = new URL(href);
+                            if (linkUrl.hostname && linkUrl.hostname === domain) {
+                                links.internal.push(linkObj);
+                            } else {
+                                links.external.push(linkObj);
+                            }
+                        } catch (e) {
                             links.external.push(linkObj);
                         }
-                    } catch (e) {
-                        links.external.push(linkObj);
-                    }
-                });
+                    });
 
-                return { media, links };
-            })()
-        "#;
+                    return { media, links };
+                })()
+            "#;
 
-        let extraction: Option<ExtractionResult> = match page.evaluate(script).await {
-            Ok(val) => match val.into_value() {
-                Ok(v) => Some(v),
+            let extraction: Option<ExtractionResult> = match page.evaluate(script).await {
+                Ok(val) => match val.into_value() {
+                    Ok(v) => Some(v),
+                    Err(e) => {
+                        eprintln!("Failed to deserialize extraction result: {:?}", e);
+                        None
+                    }
+                },
                 Err(e) => {
-                    eprintln!("Failed to deserialize extraction result: {:?}", e);
+                    eprintln!("Failed to evaluate extraction script: {:?}", e);
                     None
                 }
-            },
-            Err(e) => {
-                eprintln!("Failed to evaluate extraction script: {:?}", e);
-                None
+            };
+
+            Ok((status_code, response_headers, redirected_url, redirect_chain, js_execution_result, html, screenshot_data, extraction))
+        }
+        .await;
+
+        // Stop intercepting and close the page on every exit, including failed navigations
+        if let Some(interception) = interception {
+            interception.stop().await;
+        }
+
+        // Session pages stay open for later crawls in the session
+        if session_page.is_none() {
+            let closed = page.close().await;
+            if loaded.is_ok() {
+                closed?;
             }
+        }
+
+        let (status_code, response_headers, redirected_url, redirect_chain, js_execution_result, html, screenshot_data, extraction) =
+            loaded?;
+
+        let (media, links) = if let Some(ext) = extraction {
+            (Some(ext.media), Some(ext.links))
+        } else {
+            (None, None)
+        };
+
+        let result = CrawlResult {
+            url: url.to_string(),
+            html,
+            success: true,
+            cleaned_html: None,
+            media,
+            links,
+            screenshot: screenshot_data,
+            markdown: None,
+            extracted_content: None,
+            error_message: None,
+            depth: None,
+            parent_url: None,
+            metadata: None,
+            status_code,
+            response_headers,
+            redirected_url,
+            redirect_chain,
+            js_execution_result,
         };
 
-        page.close().await?;
+        Ok(Self::process_html(result, config).await)
+    }
+
+    /// Cleans the HTML of `result`, reads its metadata, generates markdown and runs the extraction strategy.
+    ///
+    /// This needs no browser, so it is also used to re-process cached results.
+    async fn process_html(mut result: CrawlResult, config: &Option<CrawlerRunConfig>) -> CrawlResult {
+        let scraping_strategy = config.as_ref().and_then(|c| c.scraping_strategy.clone()).unwrap_or_default();
+        // Relative URLs resolve against the page's `<base href>`, then the URL after redirects
+        let page_url = result.redirected_url.clone().unwrap_or_else(|| result.url.clone());
+        let base_url = crawler_strategy::base_url(&result.html, &page_url);
+
+        // `css_selector` scopes everything but the raw HTML, `target_elements` only the content
+        let page_html = match config.as_ref().and_then(|c| c.css_selector.as_deref()) {
+            Some(selector) => {
+                let selected = content_scraping::select_content(&result.html, selector);
+                let (media, links) = crawler_strategy::extract_media_and_links(&selected, &page_url);
+                result.media = Some(media);
+                result.links = Some(links);
+                selected
+            }
+            None => result.html.clone(),
+        };
+        let cleaned_html = scraping_strategy.scrape(&page_html);
+        let target_html = config
+            .as_ref()
+            .filter(|c| !c.target_elements.is_empty())
+            .map(|c| content_scraping::select_content(&page_html, &c.target_elements.join(", ")));
+        let content_html = target_html.as_deref().unwrap_or(&page_html);
 
         // Generate Markdown
         let content_filter = if let Some(ref cfg) = config {
@@ -529,22 +1449,22 @@ impl AsyncWebCrawler {
             ContentFilter::Pruning(PruningContentFilter::default())
         };
 
-        let generator = DefaultMarkdownGenerator::new(Some(content_filter));
-        let markdown_result = generator.generate_markdown(&html).await;
-
-        let (media, links) = if let Some(ext) = extraction {
-            (Some(ext.media), Some(ext.links))
-        } else {
-            (None, None)
+        let markdown_options = config.as_ref().and_then(|c| c.markdown_options.clone()).unwrap_or_default();
+        let generator = DefaultMarkdownGenerator::new(Some(content_filter)).with_options(markdown_options);
+        let markdown_html = match config.as_ref().map(|c| c.markdown_source).unwrap_or_default() {
+            MarkdownSource::RawHtml => content_html.to_string(),
+            MarkdownSource::CleanedHtml if target_html.is_some() => scraping_strategy.scrape(content_html),
+            MarkdownSource::CleanedHtml => cleaned_html.clone(),
         };
+        let markdown_result = generator.generate_markdown(&markdown_html, &base_url).await;
 
         // Execute extraction strategy if present
         let extracted_content = if let Some(ref cfg) = config {
             if let Some(ref strategy) = cfg.extraction_strategy {
                  let results = match strategy {
-                     ExtractionStrategyConfig::JsonCss(s) => s.extract(&html),
-                     ExtractionStrategyConfig::JsonXPath(s) => s.extract(&html),
-                     ExtractionStrategyConfig::Regex(s) => s.extract(url, &html),
+                     ExtractionStrategyConfig::JsonCss(s) => s.extract(content_html),
+                     ExtractionStrategyConfig::JsonXPath(s) => s.extract(content_html),
+                     ExtractionStrategyConfig::Regex(s) => s.extract(&result.url, content_html),
                  };
                  match serde_json::to_string(&results) {
                      Ok(s) => Some(s),
@@ -560,18 +1480,11 @@ impl AsyncWebCrawler {
             None
         };
 
-        Ok(CrawlResult {
-            url: url.to_string(),
-            html,
-            success: true,
-            cleaned_html: None,
-            media,
-            links,
-            screenshot: screenshot_data,
-            markdown: Some(markdown_result),
-            extracted_content,
-            error_message: None,
-        })
+        result.metadata = Some(metadata::extract_metadata(&result.html, &result.url));
+        result.cleaned_html = Some(cleaned_html);
+        result.markdown = Some(markdown_result);
+        result.extracted_content = extracted_content;
+        result
     }
 }
 
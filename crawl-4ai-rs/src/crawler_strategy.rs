@@ -0,0 +1,497 @@
+use crate::crawler::CrawlerError;
+use crate::models::{BrowserConfig, CrawlResult, CrawlerRunConfig, HttpCrawlerConfig, Link, MediaItem, Redirect};
+use crate::proxy_strategy::ProxyConfig;
+use crate::rate_limiter::{RateLimiter, parse_retry_after};
+use anyhow::Result;
+use kuchiki::traits::*;
+use kuchiki::NodeRef;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_LANGUAGE, AUTHORIZATION, CONTENT_TYPE, COOKIE, LOCATION,
+    PROXY_AUTHORIZATION, RETRY_AFTER, USER_AGENT,
+};
+use reqwest::{redirect, Client, Response};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use url::Url;
+
+/// User agent sent by the HTTP strategy when the browser config has no custom user agent.
+pub const DEFAULT_HTTP_USER_AGENT: &str = "Mozilla/5.0 (compatible; crawl4ai)";
+
+/// Fetches pages over plain HTTP with `reqwest` instead of rendering them in Chromium.
+///
+/// Links and media are extracted by parsing the HTML with `kuchiki`, so pages that build
+/// their content with JavaScript will look different than on the browser path. Wait
+/// strategies, screenshots and `js_code` need a browser and are ignored. Responses that are
+/// not text, such as images or PDFs, are refused.
+pub struct HttpCrawlerStrategy {
+    client: Client,
+    headers: HeaderMap,
+    /// Headers from the config, sent per request so that credentials can be withheld from
+    /// other origins.
+    request_headers: HeaderMap,
+    timeout: Duration,
+    max_redirects: usize,
+    max_body_bytes: usize,
+    /// Clients for per-run proxies, built on first use.
+    proxy_clients: Mutex<HashMap<ProxyConfig, Client>>,
+}
+
+impl HttpCrawlerStrategy {
+    /// Creates a new `HttpCrawlerStrategy`. The user agent and locale are taken from `browser_config`.
+    pub fn new(config: &HttpCrawlerConfig, browser_config: &BrowserConfig) -> Self {
+        let headers = identity_headers(browser_config);
+        let mut request_headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                (Ok(name), Ok(value)) => { request_headers.insert(name, value); }
+                _ => eprintln!("Ignoring invalid header {:?}", name),
+            }
+        }
+
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let client = Self::build_client(&headers, timeout, browser_config.proxy.as_ref()).unwrap_or_else(|e| {
+            eprintln!("Failed to build HTTP client, using defaults: {}", e);
+            Client::new()
+        });
+
+        Self {
+            client,
+            headers,
+            request_headers,
+            timeout,
+            max_redirects: config.max_redirects,
+            max_body_bytes: config.max_body_bytes,
+            proxy_clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn build_client(headers: &HeaderMap, timeout: Duration, proxy: Option<&ProxyConfig>) -> Result<Client> {
+        let mut builder = Client::builder()
+            .default_headers(headers.clone())
+            .timeout(timeout)
+            // Redirects are followed in `crawl` so that every hop can be recorded
+            .redirect(redirect::Policy::none());
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy.to_reqwest()?);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Returns the client sending requests through `proxy`, or the default client.
+    fn client_for(&self, proxy: Option<&ProxyConfig>) -> Result<Client> {
+        let Some(proxy) = proxy else { return Ok(self.client.clone()) };
+        let mut clients = self.proxy_clients.lock().unwrap();
+        if let Some(client) = clients.get(proxy) {
+            return Ok(client.clone());
+        }
+        let client = Self::build_client(&self.headers, self.timeout, Some(proxy))
+            .map_err(|e| CrawlerError::NavigationError(format!("Invalid proxy {}: {}", proxy.server, e)))?;
+        clients.insert(proxy.clone(), client.clone());
+        Ok(client)
+    }
+
+    /// Fetches `url`, through `proxy` if given, and returns its HTML with links and media.
+    /// Markdown and extraction are left to the caller.
+    pub async fn crawl(
+        &self,
+        url: &str,
+        config: &Option<CrawlerRunConfig>,
+        rate_limiter: Option<&RateLimiter>,
+        proxy: Option<&ProxyConfig>,
+    ) -> Result<CrawlResult> {
+        if let Some(ref cfg) = config {
+            if cfg.wait_for.is_some() || cfg.screenshot || !cfg.js_code.is_empty() || cfg.js_only {
+                eprintln!("Wait strategies, screenshots and JavaScript need the browser strategy; ignoring them for {}", url);
+            }
+        }
+        let timeout = config.as_ref().and_then(|c| c.page_timeout).map(Duration::from_millis);
+        let client = self.client_for(proxy)?;
+
+        let mut current_url = Url::parse(url).map_err(|e| CrawlerError::NavigationError(format!("Invalid URL {}: {}", url, e)))?;
+        let origin = current_url.origin();
+        let mut redirect_chain = Vec::new();
+        let mut left_origin = false;
+        let response = loop {
+            // Like reqwest's own redirect policy, credentials only go to the original origin
+            left_origin |= current_url.origin() != origin;
+            let mut headers = self.request_headers.clone();
+            if left_origin {
+                for name in [AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION] {
+                    headers.remove(name);
+                }
+            }
+            let mut request = client.get(current_url.clone()).headers(headers);
+            if let Some(timeout) = timeout {
+                request = request.timeout(timeout);
+            }
+            let response = request.send().await.map_err(|e| {
+                if e.is_timeout() {
+                    CrawlerError::Timeout("Page navigation timed out".to_string())
+                } else {
+                    CrawlerError::NavigationError(e.to_string())
+                }
+            })?;
+
+            let location = response.headers().get(LOCATION).and_then(|v| v.to_str().ok());
+            let next_url = match location {
+                Some(location) if response.status().is_redirection() => current_url.join(location).ok(),
+                _ => None,
+            };
+            let Some(next_url) = next_url else { break response };
+
+            if redirect_chain.len() >= self.max_redirects {
+                return Err(CrawlerError::NavigationError(format!("Too many redirects fetching {}", url)).into());
+            }
+            redirect_chain.push(Redirect { url: current_url.to_string(), status_code: response.status().as_u16() });
+            current_url = next_url;
+        };
+
+        let status = response.status().as_u16() as i64;
+        if let Some(limiter) = rate_limiter {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after);
+            if !limiter.update_delay(url, status, retry_after) {
+                eprintln!("Rate limit retries exhausted for {}", url);
+            }
+        }
+        if status == 404 {
+            return Err(CrawlerError::HttpStatusCode(404).into());
+        }
+        if status >= 400 {
+            eprintln!("Page returned status: {}", status);
+            return Err(CrawlerError::HttpStatusCode(status).into());
+        }
+
+        let content_type = response.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).map(str::to_string);
+        if let Some(ref content_type) = content_type {
+            if !is_text_content_type(content_type) {
+                return Err(CrawlerError::NavigationError(format!("Not an HTML page ({}): {}", content_type, url)).into());
+            }
+        }
+
+        // Resolve relative links against the URL we ended up at after redirects
+        let final_url = response.url().to_string();
+        let response_headers = headers_map(response.headers());
+        let (body, truncated) = read_body(response, self.max_body_bytes, |_| false)
+            .await
+            .map_err(|e| CrawlerError::NavigationError(format!("Failed to read response body: {}", e)))?;
+        if truncated {
+            return Err(CrawlerError::NavigationError(format!(
+                "Page larger than {} bytes: {}",
+                self.max_body_bytes, url
+            ))
+            .into());
+        }
+        let html = decode_body(&body, content_type.as_deref());
+
+        let (media, links) = extract_media_and_links(&html, &final_url);
+
+        Ok(CrawlResult {
+            url: url.to_string(),
+            html,
+            success: true,
+            media: Some(media),
+            links: Some(links),
+            status_code: Some(status as u16),
+            response_headers: Some(response_headers),
+            redirected_url: Some(final_url),
+            redirect_chain,
+            ..Default::default()
+        })
+    }
+}
+
+/// Returns whether `content_type` is HTML or other text. Servers often label HTML as plain text.
+fn is_text_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    mime.is_empty() || mime.starts_with("text/") || mime == "application/xhtml+xml" || mime == "application/xml"
+}
+
+/// Decodes `body` with the charset of `content_type`, falling back to UTF-8.
+fn decode_body(body: &[u8], content_type: Option<&str>) -> String {
+    let encoding = content_type
+        .and_then(|ct| ct.split(';').skip(1).find_map(|param| param.trim().strip_prefix("charset=")))
+        .and_then(|label| encoding_rs::Encoding::for_label(label.trim_matches('"').as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(body).0.into_owned()
+}
+
+/// Reads the body of `response` until `done` returns true for what was read so far, or up
+/// to `limit` bytes. Returns the bytes and whether the limit cut the body short.
+pub(crate) async fn read_body(
+    mut response: Response,
+    limit: usize,
+    done: impl Fn(&[u8]) -> bool,
+) -> reqwest::Result<(Vec<u8>, bool)> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() > limit {
+            body.truncate(limit);
+            return Ok((body, true));
+        }
+        if done(&body) {
+            break;
+        }
+    }
+    Ok((body, false))
+}
+
+/// Reads `response` up to the end of its `<head>`, or at most `limit` bytes.
+pub(crate) async fn read_head(response: Response, limit: usize) -> reqwest::Result<Vec<u8>> {
+    let (head, _) = read_body(response, limit, |body| {
+        body.windows(7).any(|w| w.eq_ignore_ascii_case(b"</head>"))
+    })
+    .await?;
+    Ok(head)
+}
+
+/// Headers presenting the crawler to sites: accepted types, user agent and locale.
+fn identity_headers(browser_config: &BrowserConfig) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, HeaderValue::from_static("text/html,application/xhtml+xml,*/*;q=0.8"));
+
+    let user_agent = browser_config.user_agent.as_deref().unwrap_or(DEFAULT_HTTP_USER_AGENT);
+    match HeaderValue::from_str(user_agent) {
+        Ok(value) => { headers.insert(USER_AGENT, value); }
+        Err(e) => eprintln!("Ignoring invalid user agent {:?}: {}", user_agent, e),
+    }
+
+    if let Some(ref locale) = browser_config.locale {
+        if let Ok(value) = HeaderValue::from_str(locale) {
+            headers.insert(ACCEPT_LANGUAGE, value);
+        }
+    }
+    headers
+}
+
+/// Builds a client for side requests made on behalf of a crawl, such as those of deep crawl
+/// filters. It presents itself like the crawler, goes through `proxy` and follows redirects.
+pub(crate) fn site_client(browser_config: &BrowserConfig, proxy: Option<&ProxyConfig>) -> Result<Client> {
+    let mut builder = Client::builder().default_headers(identity_headers(browser_config));
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy.to_reqwest()?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Collects response headers into a map keyed by lowercased name. Repeated headers are
+/// joined with `, `.
+fn headers_map(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut map: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+        map.entry(name.as_str().to_string())
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    map
+}
+
+/// Returns whether `url` is a `raw:` or `file://` URL, which are loaded without a browser.
+pub fn is_local_url(url: &str) -> bool {
+    url.starts_with("raw:") || url.starts_with("file://")
+}
+
+/// Loads a `raw:<html>` or `file://` URL into a `CrawlResult` with links and media.
+///
+/// Returns `None` for any other URL. Markdown and extraction are left to the caller.
+pub async fn crawl_local(url: &str) -> Result<Option<CrawlResult>> {
+    let (html, page_url) = if let Some(html) = url.strip_prefix("raw://").or_else(|| url.strip_prefix("raw:")) {
+        // Raw HTML has no location, so relative links are left as they are.
+        (html.to_string(), "")
+    } else if url.starts_with("file://") {
+        let path = Url::parse(url)
+            .ok()
+            .and_then(|u| u.to_file_path().ok())
+            .ok_or_else(|| CrawlerError::NavigationError(format!("Invalid file URL: {}", url)))?;
+        let html = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| CrawlerError::NavigationError(format!("Failed to read {}: {}", path.display(), e)))?;
+        (html, url)
+    } else {
+        return Ok(None);
+    };
+
+    let (media, links) = extract_media_and_links(&html, page_url);
+
+    Ok(Some(CrawlResult {
+        url: url.to_string(),
+        html,
+        success: true,
+        media: Some(media),
+        links: Some(links),
+        ..Default::default()
+    }))
+}
+
+/// Returns the URL relative links of `html` resolve against: its `<base href>` if it has
+/// one, otherwise `page_url`.
+pub fn base_url(html: &str, page_url: &str) -> String {
+    let document = kuchiki::parse_html().one(html);
+    document_base(&document, Url::parse(page_url).ok().as_ref())
+        .map(String::from)
+        .unwrap_or_else(|| page_url.to_string())
+}
+
+fn document_base(document: &NodeRef, page: Option<&Url>) -> Option<Url> {
+    document
+        .select_first("base[href]")
+        .ok()
+        .and_then(|node| {
+            let href = node.attributes.borrow().get("href")?.to_string();
+            match page {
+                Some(page) => page.join(&href).ok(),
+                None => Url::parse(&href).ok(),
+            }
+        })
+        .or_else(|| page.cloned())
+}
+
+/// Extracts images and links from `html`, mirroring the script the browser path injects.
+///
+/// URLs are resolved against the page's `<base href>` if it has one, otherwise against
+/// `page_url`. Links to the host of `page_url` are internal, all others external. Links
+/// that cannot be resolved because there is no base URL are relative and thus internal.
+pub fn extract_media_and_links(
+    html: &str,
+    page_url: &str,
+) -> (HashMap<String, Vec<MediaItem>>, HashMap<String, Vec<Link>>) {
+    let document = kuchiki::parse_html().one(html);
+    let page = Url::parse(page_url).ok();
+    let base = document_base(&document, page.as_ref());
+
+    let resolve = |href: &str| match base {
+        Some(ref base) => base.join(href).map(|u| u.to_string()).unwrap_or_else(|_| href.to_string()),
+        None => href.to_string(),
+    };
+    let non_empty = |value: Option<&str>| value.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+
+    let mut images = Vec::new();
+    if let Ok(nodes) = document.select("img[src]") {
+        for node in nodes {
+            let attrs = node.attributes.borrow();
+            let Some(src) = non_empty(attrs.get("src")) else { continue };
+            images.push(MediaItem {
+                src: Some(resolve(&src)),
+                alt: non_empty(attrs.get("alt")),
+                desc: non_empty(attrs.get("title")),
+                score: None,
+                type_: "image".to_string(),
+                group_id: None,
+            });
+        }
+    }
+
+    let mut internal = Vec::new();
+    let mut external = Vec::new();
+    if let Ok(nodes) = document.select("a[href], area[href]") {
+        for node in nodes {
+            let attrs = node.attributes.borrow();
+            let href = resolve(attrs.get("href").unwrap_or_default().trim());
+            let text = node.as_node().text_contents().split_whitespace().collect::<Vec<_>>().join(" ");
+
+            let is_internal = match (Url::parse(&href), page.as_ref()) {
+                (Ok(link), Some(page)) => {
+                    matches!(link.scheme(), "http" | "https" | "file") && link.host_str() == page.host_str()
+                }
+                (Ok(_), None) => false,
+                (Err(_), _) => true,
+            };
+
+            let link = Link {
+                href: Some(href),
+                text: non_empty(Some(&text)),
+                title: non_empty(attrs.get("title")),
+            };
+            if is_internal {
+                internal.push(link);
+            } else {
+                external.push(link);
+            }
+        }
+    }
+
+    let media = HashMap::from([("images".to_string(), images)]);
+    let links = HashMap::from([
+        ("internal".to_string(), internal),
+        ("external".to_string(), external),
+    ]);
+    (media, links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"
+        <html><body>
+            <img src="/logo.png" alt="Logo" title="Our logo">
+            <img src="">
+            <a href="/about" title="About us">About
+                us</a>
+            <a href="https://other.example.org/x">Elsewhere</a>
+            <a href="mailto:team@example.com"></a>
+        </body></html>
+    "#;
+
+    #[test]
+    fn test_extract_media_and_links() {
+        let (media, links) = extract_media_and_links(PAGE, "https://example.com/docs/page");
+
+        let images = &media["images"];
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].src.as_deref(), Some("https://example.com/logo.png"));
+        assert_eq!(images[0].alt.as_deref(), Some("Logo"));
+        assert_eq!(images[0].desc.as_deref(), Some("Our logo"));
+
+        let internal = &links["internal"];
+        assert_eq!(internal.len(), 1);
+        assert_eq!(internal[0].href.as_deref(), Some("https://example.com/about"));
+        assert_eq!(internal[0].text.as_deref(), Some("About us"));
+        assert_eq!(internal[0].title.as_deref(), Some("About us"));
+
+        let external: Vec<_> = links["external"].iter().filter_map(|l| l.href.as_deref()).collect();
+        assert_eq!(external, vec!["https://other.example.org/x", "mailto:team@example.com"]);
+    }
+
+    #[test]
+    fn test_links_without_base_url() {
+        let html = r#"<a href="/docs">Docs</a><a href="https://example.com/">Home</a>"#;
+        let (_, links) = extract_media_and_links(html, "");
+        assert_eq!(links["internal"][0].href.as_deref(), Some("/docs"));
+        assert_eq!(links["external"][0].href.as_deref(), Some("https://example.com/"));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_local() {
+        let raw = crawl_local("raw:<p>Hello <a href=\"a.html\">A</a></p>").await.unwrap().unwrap();
+        assert_eq!(raw.html, "<p>Hello <a href=\"a.html\">A</a></p>");
+        assert_eq!(raw.links.unwrap()["internal"].len(), 1);
+
+        let path = std::env::temp_dir().join(format!("crawl4ai-local-{}.html", std::process::id()));
+        std::fs::write(&path, "<a href=\"other.html\">Other</a>").unwrap();
+        let file_url = Url::from_file_path(&path).unwrap().to_string();
+        let file = crawl_local(&file_url).await.unwrap().unwrap();
+        let links = file.links.unwrap();
+        assert!(links["internal"][0].href.as_deref().unwrap().ends_with("/other.html"));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(crawl_local("file:///does/not/exist.html").await.is_err());
+        assert!(crawl_local("https://example.com").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_base_href() {
+        let html = r#"<html><head><base href="/static/"></head><body><a href="a.html">A</a></body></html>"#;
+        let (_, links) = extract_media_and_links(html, "https://example.com/docs/page");
+        assert_eq!(links["internal"][0].href.as_deref(), Some("https://example.com/static/a.html"));
+    }
+}
@@ -0,0 +1,169 @@
+use super::{DeepCrawlStrategy, PendingUrl};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Where and how often a deep crawl saves its progress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckpointConfig {
+    /// File the crawl state is written to (default: `crawl_checkpoint.json`).
+    pub path: PathBuf,
+    /// Number of pages crawled between saves. The state is also saved when the crawl ends (default: 10).
+    pub interval: usize,
+    /// Whether to continue from the state in `path` if the file exists (default: false, so
+    /// the file is overwritten). A checkpoint saved for another start URL or strategy is ignored.
+    pub resume: bool,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("crawl_checkpoint.json"),
+            interval: 10,
+            resume: false,
+        }
+    }
+}
+
+impl CheckpointConfig {
+    /// Creates a new `CheckpointConfig` saving to `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Continues from the state in `path` if the file exists.
+    pub fn with_resume(mut self) -> Self {
+        self.resume = true;
+        self
+    }
+}
+
+/// What happened to a URL discovered by a deep crawl.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UrlStatus {
+    /// Waiting in the frontier.
+    Queued,
+    /// Crawled successfully.
+    Succeeded,
+    /// Crawled, but the crawl failed.
+    Failed,
+    /// Rejected by the filter chain.
+    Filtered,
+}
+
+/// Saved state of a deep crawl, from which it can be resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlCheckpoint {
+    /// URL the crawl started from.
+    pub start_url: String,
+    /// Traversal order of the crawl.
+    pub strategy: DeepCrawlStrategy,
+    /// URLs waiting to be crawled.
+    pub frontier: Vec<PendingUrl>,
+    /// Depth of every URL discovered so far.
+    pub depths: BTreeMap<String, usize>,
+    /// Status of every URL discovered so far.
+    pub statuses: BTreeMap<String, UrlStatus>,
+    /// Number of pages crawled successfully.
+    pub pages_crawled: usize,
+    /// Discovery counter used to order the frontier.
+    pub next_seq: u64,
+    /// When the checkpoint was written, as an HTTP date.
+    pub saved_at: String,
+}
+
+impl CrawlCheckpoint {
+    /// Reads a checkpoint written by [`CrawlCheckpoint::save`].
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read checkpoint {}", path.display()))?;
+        serde_json::from_slice(&content).with_context(|| format!("Failed to parse checkpoint {}", path.display()))
+    }
+
+    /// Writes the checkpoint to `path`.
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        // Write to a temporary file first so that a crash never leaves a partial checkpoint. Its
+        // name is unique so that two crawls saving to the same path do not interleave.
+        static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+        let tmp = path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        if let Err(e) = tokio::fs::write(&tmp, serde_json::to_vec_pretty(self)?).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(e.into());
+        }
+        tokio::fs::rename(&tmp, path).await?;
+        Ok(())
+    }
+
+    /// Returns the number of URLs with each status.
+    pub fn status_counts(&self) -> BTreeMap<UrlStatus, usize> {
+        let mut counts = BTreeMap::new();
+        for status in self.statuses.values() {
+            *counts.entry(*status).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Returns whether the crawl had nothing left to do when the checkpoint was written.
+    pub fn is_finished(&self) -> bool {
+        self.frontier.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("crawl4ai-checkpoint-{}/state.json", std::process::id()));
+        let checkpoint = CrawlCheckpoint {
+            start_url: "https://example.com/".to_string(),
+            strategy: DeepCrawlStrategy::Dfs,
+            frontier: vec![PendingUrl {
+                url: "https://example.com/b".to_string(),
+                parent_url: Some("https://example.com/".to_string()),
+                depth: 1,
+                score: 0.5,
+                seq: 2,
+            }],
+            depths: BTreeMap::from([("https://example.com/".to_string(), 0), ("https://example.com/b".to_string(), 1)]),
+            statuses: BTreeMap::from([
+                ("https://example.com/".to_string(), UrlStatus::Succeeded),
+                ("https://example.com/a".to_string(), UrlStatus::Filtered),
+                ("https://example.com/b".to_string(), UrlStatus::Queued),
+            ]),
+            pages_crawled: 1,
+            next_seq: 3,
+            saved_at: httpdate::fmt_http_date(std::time::SystemTime::now()),
+        };
+
+        checkpoint.save(&path).await.unwrap();
+        let loaded = CrawlCheckpoint::load(&path).await.unwrap();
+
+        assert_eq!(loaded.frontier[0].url, "https://example.com/b");
+        assert_eq!(loaded.strategy, DeepCrawlStrategy::Dfs);
+        assert_eq!(loaded.next_seq, 3);
+        assert_eq!(loaded.status_counts()[&UrlStatus::Filtered], 1);
+        assert!(!loaded.is_finished());
+        // No temporary file is left behind
+        assert_eq!(std::fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
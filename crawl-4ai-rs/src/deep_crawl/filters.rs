@@ -0,0 +1,689 @@
+use crate::crawler_strategy::read_head;
+use crate::rate_limiter::{parse_retry_after, RateLimiter};
+use crate::robots::RobotsChecker;
+use kuchiki::traits::*;
+use regex::Regex;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, RETRY_AFTER};
+use reqwest::{Client, Method, Response};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use url::Url;
+
+/// Largest prefix of a page the `SeoFilter` reads while looking for the end of its `<head>`.
+const MAX_HEAD_BYTES: usize = 256 * 1024;
+
+/// Counts how many URLs a filter has seen, passed and rejected.
+///
+/// Clones share the same counters, so the statistics of a filter can be read through a
+/// clone kept aside before the filter was handed to a crawl.
+#[derive(Debug, Clone, Default)]
+pub struct FilterStats {
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    total: AtomicUsize,
+    passed: AtomicUsize,
+    rejected: AtomicUsize,
+}
+
+impl FilterStats {
+    /// Returns the number of URLs checked.
+    pub fn total(&self) -> usize {
+        self.counters.total.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of URLs that passed.
+    pub fn passed(&self) -> usize {
+        self.counters.passed.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of URLs that were rejected.
+    pub fn rejected(&self) -> usize {
+        self.counters.rejected.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, passed: bool) {
+        self.counters.total.fetch_add(1, Ordering::Relaxed);
+        if passed {
+            self.counters.passed.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A filter deciding whether a discovered URL should be crawled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UrlFilter {
+    Domain(DomainFilter),
+    UrlPattern(UrlPatternFilter),
+    ContentType(ContentTypeFilter),
+    Seo(SeoFilter),
+}
+
+/// How the filters that send requests reach the site: through the crawler's client (user
+/// agent, proxy) and after waiting for its rate limiter, like the crawl itself. When the run
+/// checks `robots.txt`, disallowed URLs are rejected without any request.
+#[derive(Debug, Clone)]
+pub(crate) struct FilterRequests {
+    pub(crate) client: Client,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    /// The robots.txt checker and the user agent matched against it.
+    pub(crate) robots: Option<(Arc<RobotsChecker>, String)>,
+}
+
+impl UrlFilter {
+    /// Returns whether `url` passes this filter, recording the outcome in its statistics.
+    pub async fn apply(&self, url: &str) -> bool {
+        self.apply_with(url, None).await
+    }
+
+    async fn apply_with(&self, url: &str, requests: Option<&FilterRequests>) -> bool {
+        let passed = match self {
+            UrlFilter::Domain(f) => f.accepts(url),
+            UrlFilter::UrlPattern(f) => f.accepts(url),
+            UrlFilter::ContentType(f) => f.accepts_with(url, requests).await,
+            UrlFilter::Seo(f) => f.accepts_with(url, requests).await,
+        };
+        self.stats().record(passed);
+        passed
+    }
+
+    /// Returns the statistics of this filter.
+    pub fn stats(&self) -> &FilterStats {
+        match self {
+            UrlFilter::Domain(f) => &f.stats,
+            UrlFilter::UrlPattern(f) => &f.stats,
+            UrlFilter::ContentType(f) => &f.stats,
+            UrlFilter::Seo(f) => &f.stats,
+        }
+    }
+}
+
+/// A sequence of filters that a URL must all pass.
+///
+/// Filters are applied in order and evaluation stops at the first rejection, so cheap
+/// filters should come before ones that send requests.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FilterChain {
+    pub filters: Vec<UrlFilter>,
+    #[serde(skip)]
+    stats: FilterStats,
+    #[serde(skip)]
+    requests: Option<FilterRequests>,
+}
+
+impl FilterChain {
+    /// Creates a new `FilterChain` from `filters`.
+    pub fn new(filters: Vec<UrlFilter>) -> Self {
+        Self {
+            filters,
+            stats: FilterStats::default(),
+            requests: None,
+        }
+    }
+
+    /// Sends the requests of the filters through `requests` instead of a plain client.
+    pub(crate) fn set_requests(&mut self, requests: FilterRequests) {
+        self.requests = Some(requests);
+    }
+
+    /// Appends `filter` to the chain.
+    pub fn with_filter(mut self, filter: UrlFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Returns whether `url` passes every filter in the chain.
+    pub async fn apply(&self, url: &str) -> bool {
+        let mut passed = true;
+        for filter in &self.filters {
+            if !filter.apply_with(url, self.requests.as_ref()).await {
+                passed = false;
+                break;
+            }
+        }
+        self.stats.record(passed);
+        passed
+    }
+
+    /// Returns the statistics of the chain as a whole.
+    pub fn stats(&self) -> &FilterStats {
+        &self.stats
+    }
+}
+
+/// Allows or blocks URLs by domain. A domain also matches its subdomains.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DomainFilter {
+    /// Domains to allow. An empty list allows every domain that is not blocked.
+    pub allowed_domains: Vec<String>,
+    /// Domains to reject. Takes precedence over `allowed_domains`.
+    pub blocked_domains: Vec<String>,
+    /// Outcomes of this filter so far.
+    #[serde(skip)]
+    pub stats: FilterStats,
+}
+
+impl DomainFilter {
+    /// Creates a new `DomainFilter`.
+    pub fn new(allowed_domains: Vec<String>, blocked_domains: Vec<String>) -> Self {
+        Self {
+            allowed_domains,
+            blocked_domains,
+            stats: FilterStats::default(),
+        }
+    }
+
+    /// Returns whether the domain of `url` is allowed.
+    pub fn accepts(&self, url: &str) -> bool {
+        let Some(host) = Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.to_lowercase())) else {
+            return false;
+        };
+        let matches = |domain: &String| {
+            let domain = domain.trim().trim_start_matches('.').to_lowercase();
+            host == domain || host.ends_with(&format!(".{}", domain))
+        };
+
+        if self.blocked_domains.iter().any(matches) {
+            return false;
+        }
+        self.allowed_domains.is_empty() || self.allowed_domains.iter().any(matches)
+    }
+}
+
+/// Passes URLs matching any of a set of glob or regex patterns.
+///
+/// Globs support `*` (any characters, including `/`) and `?` (a single character) and are
+/// matched against the full URL as well as its path and query, so both
+/// `https://example.com/blog/*` and `/blog/*` work. Regexes are searched in the full URL.
+///
+/// Patterns are compiled on first use, so changes to `globs` or `regexes` after that are
+/// not seen.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrlPatternFilter {
+    /// Glob patterns.
+    pub globs: Vec<String>,
+    /// Regular expressions.
+    pub regexes: Vec<String>,
+    /// Reject matching URLs and pass all others instead (default: false).
+    pub reverse: bool,
+    /// Outcomes of this filter so far.
+    #[serde(skip)]
+    pub stats: FilterStats,
+    #[serde(skip)]
+    compiled: OnceLock<CompiledPatterns>,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledPatterns {
+    globs: Vec<Regex>,
+    regexes: Vec<Regex>,
+}
+
+impl UrlPatternFilter {
+    /// Creates a new `UrlPatternFilter` from glob patterns.
+    pub fn new(globs: Vec<String>) -> Self {
+        Self {
+            globs,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a new `UrlPatternFilter` from regular expressions.
+    pub fn from_regexes(regexes: Vec<String>) -> Self {
+        Self {
+            regexes,
+            ..Default::default()
+        }
+    }
+
+    /// Returns whether `url` passes the filter.
+    pub fn accepts(&self, url: &str) -> bool {
+        let path = Url::parse(url)
+            .ok()
+            .map(|u| match u.query() {
+                Some(q) => format!("{}?{}", u.path(), q),
+                None => u.path().to_string(),
+            });
+
+        let compiled = self.compiled.get_or_init(|| CompiledPatterns {
+            globs: self.globs.iter().filter_map(|g| compile(&glob_to_regex(g))).collect(),
+            regexes: self.regexes.iter().filter_map(|r| compile(r)).collect(),
+        });
+
+        let glob_match = compiled.globs.iter().any(|re| {
+            re.is_match(url) || path.as_deref().map(|p| re.is_match(p)).unwrap_or(false)
+        });
+        let matched = glob_match || compiled.regexes.iter().any(|re| re.is_match(url));
+
+        matched != self.reverse
+    }
+}
+
+pub(crate) fn compile(pattern: &str) -> Option<Regex> {
+    match Regex::new(pattern) {
+        Ok(re) => Some(re),
+        Err(e) => {
+            eprintln!("Ignoring invalid URL pattern {:?}: {}", pattern, e);
+            None
+        }
+    }
+}
+
+pub(crate) fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    pattern
+}
+
+/// Passes URLs whose content type is in `allowed_types`.
+///
+/// The type is guessed from the file extension of the URL and, if `check_head` is set,
+/// read from the `Content-Type` of a `HEAD` response. URLs without an extension, with an
+/// unknown extension or whose `HEAD` request fails are passed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentTypeFilter {
+    /// Allowed types: MIME types (`text/html`), wildcards (`image/*`) or extensions (`pdf`).
+    pub allowed_types: Vec<String>,
+    /// Whether to check the file extension of the URL (default: true).
+    pub check_extension: bool,
+    /// Whether to send a `HEAD` request and check the `Content-Type` header (default: false).
+    pub check_head: bool,
+    /// Timeout for the `HEAD` request in milliseconds (default: 10000ms).
+    pub timeout_ms: u64,
+    /// Outcomes of this filter so far.
+    #[serde(skip)]
+    pub stats: FilterStats,
+}
+
+impl Default for ContentTypeFilter {
+    fn default() -> Self {
+        Self::new(vec!["text/html".to_string()])
+    }
+}
+
+impl ContentTypeFilter {
+    /// Creates a new `ContentTypeFilter` that checks extensions only.
+    pub fn new(allowed_types: Vec<String>) -> Self {
+        Self {
+            allowed_types,
+            check_extension: true,
+            check_head: false,
+            timeout_ms: 10_000,
+            stats: FilterStats::default(),
+        }
+    }
+
+    /// Returns whether `url` has an allowed content type.
+    pub async fn accepts(&self, url: &str) -> bool {
+        self.accepts_with(url, None).await
+    }
+
+    async fn accepts_with(&self, url: &str, requests: Option<&FilterRequests>) -> bool {
+        let Ok(parsed) = Url::parse(url) else { return false };
+
+        if self.check_extension {
+            let extension = parsed
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .and_then(|name| name.rsplit_once('.'))
+                .map(|(_, ext)| ext.to_lowercase());
+            if let Some(extension) = extension {
+                if let Some(mime) = mime_for_extension(&extension) {
+                    if !self.is_allowed(mime, Some(&extension)) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        if self.check_head {
+            match send(requests, Method::HEAD, url, Duration::from_millis(self.timeout_ms)).await {
+                None => return false,
+                Some(Ok(response)) => {
+                    if let Some(mime) = content_type(response.headers()) {
+                        return self.is_allowed(&mime, None);
+                    }
+                }
+                Some(Err(_)) => {}
+            }
+        }
+
+        true
+    }
+
+    fn is_allowed(&self, mime: &str, extension: Option<&str>) -> bool {
+        self.allowed_types.iter().any(|allowed| {
+            let allowed = allowed.trim().to_lowercase();
+            match allowed.strip_suffix("/*") {
+                Some(prefix) => mime.split('/').next() == Some(prefix),
+                None if allowed.contains('/') => allowed == mime,
+                None => Some(allowed.as_str()) == extension || mime.split('/').nth(1) == Some(allowed.as_str()),
+            }
+        })
+    }
+}
+
+/// Client shared by the filters that send requests outside of a crawl.
+fn http_client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(Client::new)
+}
+
+/// Sends a `method` request for `url` through `requests`, or the shared client without one.
+/// Returns `None` without sending anything when `robots.txt` disallows `url`.
+async fn send(
+    requests: Option<&FilterRequests>,
+    method: Method,
+    url: &str,
+    timeout: Duration,
+) -> Option<reqwest::Result<Response>> {
+    if let Some((robots, user_agent)) = requests.and_then(|r| r.robots.as_ref()) {
+        if !robots.can_fetch(url, user_agent).await {
+            return None;
+        }
+        robots.wait_for_crawl_delay(url, user_agent).await;
+    }
+
+    let client = requests.map(|r| &r.client).unwrap_or_else(|| http_client());
+    let rate_limiter = requests.and_then(|r| r.rate_limiter.as_ref());
+    if let Some(limiter) = rate_limiter {
+        limiter.wait_if_needed(url).await;
+    }
+
+    let response = match client.request(method, url).timeout(timeout).send().await {
+        Ok(response) => response,
+        Err(e) => return Some(Err(e)),
+    };
+    if let Some(limiter) = rate_limiter {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        limiter.update_delay(url, response.status().as_u16() as i64, retry_after);
+    }
+    Some(Ok(response))
+}
+
+fn content_type(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(CONTENT_TYPE)?.to_str().ok()?;
+    let mime = value.split(';').next()?.trim().to_lowercase();
+    (!mime.is_empty()).then_some(mime)
+}
+
+fn mime_for_extension(extension: &str) -> Option<&'static str> {
+    let mime = match extension {
+        "html" | "htm" | "xhtml" | "shtml" | "php" | "asp" | "aspx" | "jsp" => "text/html",
+        "txt" | "md" => "text/plain",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "js" | "mjs" => "application/javascript",
+        "json" => "application/json",
+        "xml" | "rss" | "atom" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "exe" | "bin" | "dmg" | "iso" => "application/octet-stream",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Rejects pages that ask not to be indexed or that fail simple SEO checks.
+///
+/// The page is fetched and its `X-Robots-Tag` header, `<meta name="robots">` tag,
+/// canonical link, title and description are inspected. Pages that cannot be fetched
+/// are passed, leaving the error to the crawl itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeoFilter {
+    /// Reject pages marked `noindex` (default: true).
+    pub reject_noindex: bool,
+    /// Reject pages whose canonical URL points to another page (default: false).
+    pub require_self_canonical: bool,
+    /// If not empty, require one of these keywords in the title or description (case-insensitive).
+    pub keywords: Vec<String>,
+    /// Timeout for fetching the page in milliseconds (default: 10000ms).
+    pub timeout_ms: u64,
+    /// Outcomes of this filter so far.
+    #[serde(skip)]
+    pub stats: FilterStats,
+}
+
+impl Default for SeoFilter {
+    fn default() -> Self {
+        Self {
+            reject_noindex: true,
+            require_self_canonical: false,
+            keywords: Vec::new(),
+            timeout_ms: 10_000,
+            stats: FilterStats::default(),
+        }
+    }
+}
+
+impl SeoFilter {
+    /// Creates a new `SeoFilter` that only rejects `noindex` pages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the page at `url` passes the SEO checks.
+    pub async fn accepts(&self, url: &str) -> bool {
+        self.accepts_with(url, None).await
+    }
+
+    async fn accepts_with(&self, url: &str, requests: Option<&FilterRequests>) -> bool {
+        let response = match send(requests, Method::GET, url, Duration::from_millis(self.timeout_ms)).await {
+            None => return false,
+            Some(Ok(response)) => response,
+            Some(Err(_)) => return true,
+        };
+
+        let header_noindex = response
+            .headers()
+            .get_all("x-robots-tag")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .any(|v| v.to_lowercase().contains("noindex"));
+        if self.reject_noindex && header_noindex {
+            return false;
+        }
+
+        // Only the head is checked, so the rest of the page is not downloaded
+        let final_url = response.url().clone();
+        let Ok(head) = read_head(response, MAX_HEAD_BYTES).await else { return true };
+        self.check_html(&String::from_utf8_lossy(&head), &final_url)
+    }
+
+    fn check_html(&self, html: &str, page_url: &Url) -> bool {
+        let document = kuchiki::parse_html().one(html);
+        let meta = |names: &[&str]| -> Vec<String> {
+            document
+                .select("meta[name][content]")
+                .map(|nodes| {
+                    nodes
+                        .filter_map(|node| {
+                            let attrs = node.attributes.borrow();
+                            let name = attrs.get("name")?.to_lowercase();
+                            names.contains(&name.as_str()).then(|| attrs.get("content").unwrap_or_default().to_lowercase())
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        if self.reject_noindex && meta(&["robots", "googlebot"]).iter().any(|c| c.contains("noindex")) {
+            return false;
+        }
+
+        if self.require_self_canonical {
+            let canonical = document
+                .select_first("link[rel=canonical][href]")
+                .ok()
+                .and_then(|node| node.attributes.borrow().get("href").and_then(|h| page_url.join(h).ok()));
+            if let Some(mut canonical) = canonical {
+                let mut page = page_url.clone();
+                canonical.set_fragment(None);
+                page.set_fragment(None);
+                if canonical.as_str().trim_end_matches('/') != page.as_str().trim_end_matches('/') {
+                    return false;
+                }
+            }
+        }
+
+        if !self.keywords.is_empty() {
+            let title = document
+                .select_first("title")
+                .map(|t| t.text_contents().to_lowercase())
+                .unwrap_or_default();
+            let description = meta(&["description"]).join(" ");
+            return self
+                .keywords
+                .iter()
+                .map(|k| k.to_lowercase())
+                .any(|k| title.contains(&k) || description.contains(&k));
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_domain_filter() {
+        let filter = UrlFilter::Domain(DomainFilter::new(
+            vec!["example.com".to_string()],
+            vec!["ads.example.com".to_string()],
+        ));
+
+        assert!(filter.apply("https://example.com/page").await);
+        assert!(filter.apply("https://docs.Example.com/page").await);
+        assert!(!filter.apply("https://ads.example.com/banner").await);
+        assert!(!filter.apply("https://notexample.com/").await);
+
+        assert_eq!(filter.stats().total(), 4);
+        assert_eq!(filter.stats().passed(), 2);
+        assert_eq!(filter.stats().rejected(), 2);
+    }
+
+    #[test]
+    fn test_url_pattern_filter() {
+        let globs = UrlPatternFilter::new(vec!["/blog/*".to_string(), "*://docs.example.com/*".to_string()]);
+        assert!(globs.accepts("https://example.com/blog/post-1"));
+        assert!(globs.accepts("https://docs.example.com/guide"));
+        assert!(!globs.accepts("https://example.com/shop"));
+
+        let regexes = UrlPatternFilter {
+            regexes: vec![r"/\d{4}/\d{2}/".to_string()],
+            reverse: true,
+            ..Default::default()
+        };
+        assert!(!regexes.accepts("https://example.com/2024/05/news"));
+        assert!(regexes.accepts("https://example.com/about"));
+    }
+
+    #[tokio::test]
+    async fn test_content_type_by_extension() {
+        let filter = ContentTypeFilter::new(vec!["text/html".to_string(), "pdf".to_string()]);
+        assert!(filter.accepts("https://example.com/page").await);
+        assert!(filter.accepts("https://example.com/page.php?id=1").await);
+        assert!(filter.accepts("https://example.com/paper.pdf").await);
+        assert!(filter.accepts("https://example.com/v1.2").await, "Unknown extensions pass");
+        assert!(!filter.accepts("https://example.com/logo.png").await);
+        assert!(!filter.accepts("https://example.com/archive.zip").await);
+    }
+
+    #[test]
+    fn test_seo_checks() {
+        let page = Url::parse("https://example.com/post").unwrap();
+        let filter = SeoFilter::new();
+        assert!(filter.check_html("<html><head><title>Post</title></head></html>", &page));
+        assert!(!filter.check_html(r#"<meta name="robots" content="NOINDEX, follow">"#, &page));
+
+        let strict = SeoFilter {
+            require_self_canonical: true,
+            keywords: vec!["rust".to_string()],
+            ..Default::default()
+        };
+        assert!(strict.check_html(
+            r#"<title>Learning Rust</title><link rel="canonical" href="/post/">"#,
+            &page
+        ));
+        assert!(!strict.check_html(r#"<title>Learning Rust</title><link rel="canonical" href="/other">"#, &page));
+        assert!(!strict.check_html(
+            r#"<title>Cooking</title><meta name="description" content="Recipes">"#,
+            &page
+        ));
+    }
+
+    #[test]
+    fn test_filter_chain_serde() {
+        let json = r#"{"filters": [
+            {"type": "domain", "allowed_domains": ["example.com"]},
+            {"type": "url_pattern", "globs": ["*/docs/*"]},
+            {"type": "content_type", "allowed_types": ["text/html"]},
+            {"type": "seo", "keywords": ["api"]}
+        ]}"#;
+        let chain: FilterChain = serde_json::from_str(json).unwrap();
+        assert_eq!(chain.filters.len(), 4);
+        assert!(matches!(chain.filters[3], UrlFilter::Seo(ref f) if f.reject_noindex));
+
+        let roundtrip = serde_json::to_string(&chain).unwrap();
+        assert!(roundtrip.contains(r#""type":"url_pattern""#));
+    }
+
+    #[tokio::test]
+    async fn test_filter_chain_stops_at_first_rejection() {
+        let chain = FilterChain::default()
+            .with_filter(UrlFilter::Domain(DomainFilter::new(vec!["example.com".to_string()], vec![])))
+            .with_filter(UrlFilter::UrlPattern(UrlPatternFilter::new(vec!["*/docs/*".to_string()])));
+
+        assert!(chain.apply("https://example.com/docs/intro").await);
+        assert!(!chain.apply("https://other.com/docs/intro").await);
+        assert!(!chain.apply("https://example.com/blog").await);
+
+        assert_eq!(chain.stats().passed(), 1);
+        assert_eq!(chain.stats().rejected(), 2);
+        assert_eq!(chain.filters[0].stats().total(), 3);
+        assert_eq!(chain.filters[1].stats().total(), 2, "Rejected by the domain filter first");
+    }
+}
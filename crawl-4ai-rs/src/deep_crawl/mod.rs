@@ -0,0 +1,506 @@
+pub mod checkpoint;
+pub mod filters;
+pub mod scorers;
+
+use crate::crawler::AsyncWebCrawler;
+use crate::models::{CrawlResult, CrawlerRunConfig, Link};
+use checkpoint::{CheckpointConfig, CrawlCheckpoint, UrlStatus};
+use filters::FilterChain;
+use futures::stream::{self, Stream, StreamExt};
+use scorers::{UrlScorer, UrlScorerConfig};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use url::Url;
+
+/// Number of pages crawled concurrently by the breadth-first and best-first strategies.
+const BATCH_SIZE: usize = 10;
+
+/// Number of discovered URLs checked by the filter chain at once.
+const FILTER_CONCURRENCY: usize = 5;
+
+/// Order in which a deep crawl visits the pages it discovers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DeepCrawlStrategy {
+    /// Visit every page at one depth before going deeper (default).
+    #[default]
+    Bfs,
+    /// Follow the first link of each page as deep as possible before backtracking.
+    Dfs,
+    /// Visit the highest-scoring links first. Ties are broken by depth, then discovery order.
+    BestFirst,
+}
+
+/// Configuration for following links from a start page with [`AsyncWebCrawler::adeep_crawl`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeepCrawlConfig {
+    /// Traversal order (default: breadth-first).
+    pub strategy: DeepCrawlStrategy,
+    /// Maximum number of links followed from the start page, which has depth 0 (default: 2).
+    pub max_depth: usize,
+    /// Maximum number of pages crawled successfully (default: unlimited).
+    pub max_pages: Option<usize>,
+    /// Whether to follow links to other domains (default: false).
+    pub include_external: bool,
+    /// Filters that discovered URLs must pass to be crawled (default: none).
+    ///
+    /// The start URL is always crawled. Clones of the chain share its statistics, so they
+    /// can be read from a clone kept before the crawl.
+    pub filter_chain: Option<FilterChain>,
+    /// Scorer ranking discovered links for the best-first strategy (default: none, so
+    /// links are visited in discovery order).
+    pub url_scorer: Option<UrlScorerConfig>,
+    /// Where to save the crawl state so that an interrupted crawl can be resumed (default: none).
+    pub checkpoint: Option<CheckpointConfig>,
+}
+
+impl Default for DeepCrawlConfig {
+    fn default() -> Self {
+        Self {
+            strategy: DeepCrawlStrategy::Bfs,
+            max_depth: 2,
+            max_pages: None,
+            include_external: false,
+            filter_chain: None,
+            url_scorer: None,
+            checkpoint: None,
+        }
+    }
+}
+
+impl DeepCrawlConfig {
+    /// Ranks discovered links with `scorer`, such as a closure, for the best-first strategy.
+    pub fn with_url_scorer(mut self, scorer: impl UrlScorer + 'static) -> Self {
+        self.url_scorer = Some(UrlScorerConfig::custom(scorer));
+        self
+    }
+}
+
+/// Normalizes `href` for deduplication, resolving it against `base` if given.
+///
+/// Fragments, empty queries and trailing slashes are removed. Returns `None` for URLs
+/// that cannot be crawled, such as `mailto:` links.
+pub fn normalize_url(href: &str, base: Option<&str>) -> Option<String> {
+    let mut url = match base.and_then(|b| Url::parse(b).ok()) {
+        Some(base) => base.join(href.trim()).ok()?,
+        None => Url::parse(href.trim()).ok()?,
+    };
+    if !matches!(url.scheme(), "http" | "https" | "file") {
+        return None;
+    }
+
+    url.set_fragment(None);
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+    let path = url.path().to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        url.set_path(path.trim_end_matches('/'));
+    }
+    Some(url.to_string())
+}
+
+/// A discovered URL waiting to be crawled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingUrl {
+    pub url: String,
+    /// URL of the page the link was found on, `None` for the start URL.
+    pub parent_url: Option<String>,
+    pub depth: usize,
+    /// Score given by the URL scorer, 0.0 without one.
+    pub score: f64,
+    /// Discovery order, used to break ties.
+    pub seq: u64,
+}
+
+// Ordering for the best-first heap: highest score, then shallowest, then first discovered.
+impl Ord for PendingUrl {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.depth.cmp(&self.depth))
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for PendingUrl {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for PendingUrl {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PendingUrl {}
+
+/// URLs waiting to be crawled, popped in the order of the strategy.
+enum Frontier {
+    Queue(VecDeque<PendingUrl>),
+    Stack(Vec<PendingUrl>),
+    Heap(BinaryHeap<PendingUrl>),
+}
+
+impl Frontier {
+    fn new(strategy: DeepCrawlStrategy) -> Self {
+        match strategy {
+            DeepCrawlStrategy::Bfs => Frontier::Queue(VecDeque::new()),
+            DeepCrawlStrategy::Dfs => Frontier::Stack(Vec::new()),
+            DeepCrawlStrategy::BestFirst => Frontier::Heap(BinaryHeap::new()),
+        }
+    }
+
+    /// Adds the links found on one page, given in page order.
+    fn extend(&mut self, pending: Vec<PendingUrl>) {
+        match self {
+            Frontier::Queue(queue) => queue.extend(pending),
+            // Push in reverse so that the first link on the page is visited first
+            Frontier::Stack(stack) => stack.extend(pending.into_iter().rev()),
+            Frontier::Heap(heap) => heap.extend(pending),
+        }
+    }
+
+    /// Adds one URL as is, as when restoring the order saved by [`Frontier::to_vec`].
+    fn push(&mut self, pending: PendingUrl) {
+        match self {
+            Frontier::Queue(queue) => queue.push_back(pending),
+            Frontier::Stack(stack) => stack.push(pending),
+            Frontier::Heap(heap) => heap.push(pending),
+        }
+    }
+
+    fn to_vec(&self) -> Vec<PendingUrl> {
+        match self {
+            Frontier::Queue(queue) => queue.iter().cloned().collect(),
+            Frontier::Stack(stack) => stack.clone(),
+            Frontier::Heap(heap) => heap.iter().cloned().collect(),
+        }
+    }
+
+    fn pop(&mut self) -> Option<PendingUrl> {
+        match self {
+            Frontier::Queue(queue) => queue.pop_front(),
+            Frontier::Stack(stack) => stack.pop(),
+            Frontier::Heap(heap) => heap.pop(),
+        }
+    }
+
+    fn batch_size(&self) -> usize {
+        match self {
+            // Depth-first needs the links of each page before choosing the next one
+            Frontier::Stack(_) => 1,
+            _ => BATCH_SIZE,
+        }
+    }
+}
+
+/// State of a running deep crawl.
+pub(crate) struct DeepCrawl<'a> {
+    crawler: &'a AsyncWebCrawler,
+    run_config: Option<CrawlerRunConfig>,
+    config: DeepCrawlConfig,
+    start_url: String,
+    frontier: Frontier,
+    /// Status of every URL discovered so far, used for deduplication.
+    statuses: HashMap<String, UrlStatus>,
+    depths: HashMap<String, usize>,
+    pages_crawled: usize,
+    pages_since_checkpoint: usize,
+    ready: VecDeque<CrawlResult>,
+    next_seq: u64,
+}
+
+impl<'a> DeepCrawl<'a> {
+    pub(crate) fn new(
+        crawler: &'a AsyncWebCrawler,
+        start_url: &str,
+        run_config: Option<CrawlerRunConfig>,
+        mut config: DeepCrawlConfig,
+    ) -> Self {
+        if let Some(ref mut chain) = config.filter_chain {
+            chain.set_requests(crawler.filter_requests(&run_config));
+        }
+        // URLs that cannot be normalized, such as `raw:` HTML, are crawled as given
+        let url = normalize_url(start_url, None).unwrap_or_else(|| start_url.to_string());
+        Self {
+            crawler,
+            run_config,
+            frontier: Frontier::new(config.strategy),
+            config,
+            start_url: url,
+            statuses: HashMap::new(),
+            depths: HashMap::new(),
+            pages_crawled: 0,
+            pages_since_checkpoint: 0,
+            ready: VecDeque::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Fills the frontier from the checkpoint to resume from, or with the start URL.
+    async fn start(mut self) -> Self {
+        if let Some(checkpoint) = self.load_checkpoint().await {
+            self.restore(checkpoint);
+            return self;
+        }
+
+        let url = self.start_url.clone();
+        self.statuses.insert(url.clone(), UrlStatus::Queued);
+        self.depths.insert(url.clone(), 0);
+        let pending = self.pending(url, None, 0, 0.0);
+        self.frontier.extend(vec![pending]);
+        self
+    }
+
+    /// Returns the saved state to resume from, if resuming is enabled and a checkpoint exists.
+    async fn load_checkpoint(&self) -> Option<CrawlCheckpoint> {
+        let config = self.config.checkpoint.as_ref().filter(|c| c.resume)?;
+        if !tokio::fs::try_exists(&config.path).await.unwrap_or(false) {
+            return None;
+        }
+        match CrawlCheckpoint::load(&config.path).await {
+            Ok(checkpoint) if checkpoint.start_url != self.start_url => {
+                eprintln!(
+                    "Ignoring checkpoint {}: it was saved for {}",
+                    config.path.display(),
+                    checkpoint.start_url
+                );
+                None
+            }
+            Ok(checkpoint) if checkpoint.strategy != self.config.strategy => {
+                eprintln!(
+                    "Ignoring checkpoint {}: it was saved by a {:?} crawl",
+                    config.path.display(),
+                    checkpoint.strategy
+                );
+                None
+            }
+            Ok(checkpoint) => Some(checkpoint),
+            Err(e) => {
+                eprintln!("Ignoring checkpoint: {:#}", e);
+                None
+            }
+        }
+    }
+
+    fn restore(&mut self, checkpoint: CrawlCheckpoint) {
+        for pending in checkpoint.frontier {
+            self.frontier.push(pending);
+        }
+        self.statuses = checkpoint.statuses.into_iter().collect();
+        self.depths = checkpoint.depths.into_iter().collect();
+        self.pages_crawled = checkpoint.pages_crawled;
+        self.next_seq = checkpoint.next_seq;
+    }
+
+    /// Writes the crawl state to the checkpoint file, if one is configured.
+    async fn save_checkpoint(&mut self) {
+        let Some(ref config) = self.config.checkpoint else { return };
+        self.pages_since_checkpoint = 0;
+
+        let checkpoint = CrawlCheckpoint {
+            start_url: self.start_url.clone(),
+            strategy: self.config.strategy,
+            frontier: self.frontier.to_vec(),
+            depths: self.depths.iter().map(|(url, depth)| (url.clone(), *depth)).collect(),
+            statuses: self.statuses.iter().map(|(url, status)| (url.clone(), *status)).collect(),
+            pages_crawled: self.pages_crawled,
+            next_seq: self.next_seq,
+            saved_at: httpdate::fmt_http_date(std::time::SystemTime::now()),
+        };
+        if let Err(e) = checkpoint.save(&config.path).await {
+            eprintln!("Failed to save checkpoint {}: {:#}", config.path.display(), e);
+        }
+    }
+
+    /// Turns the crawl into a stream of results, yielded as each batch of pages finishes.
+    pub(crate) fn into_stream(self) -> impl Stream<Item = CrawlResult> + 'a {
+        stream::once(self.start()).flat_map(|crawl| stream::unfold(crawl, |crawl| crawl.next()))
+    }
+
+    async fn next(mut self) -> Option<(CrawlResult, Self)> {
+        loop {
+            if let Some(result) = self.ready.pop_front() {
+                return Some((result, self));
+            }
+
+            let batch = self.next_batch();
+            if batch.is_empty() {
+                self.save_checkpoint().await;
+                return None;
+            }
+            self.crawl_batch(batch).await;
+
+            let interval = self.config.checkpoint.as_ref().map(|c| c.interval.max(1));
+            if interval.is_some_and(|interval| self.pages_since_checkpoint >= interval) {
+                self.save_checkpoint().await;
+            }
+        }
+    }
+
+    fn remaining_pages(&self) -> usize {
+        self.config
+            .max_pages
+            .map(|max| max.saturating_sub(self.pages_crawled))
+            .unwrap_or(usize::MAX)
+    }
+
+    fn next_batch(&mut self) -> Vec<PendingUrl> {
+        let size = self.frontier.batch_size().min(self.remaining_pages());
+        std::iter::from_fn(|| self.frontier.pop()).take(size).collect()
+    }
+
+    async fn crawl_batch(&mut self, batch: Vec<PendingUrl>) {
+        let urls: Vec<String> = batch.iter().map(|p| p.url.clone()).collect();
+        let results = match self.crawler.arun_many(urls.clone(), self.run_config.clone(), None).await {
+            Ok(results) => results,
+            Err(e) => urls
+                .into_iter()
+                .map(|url| CrawlResult {
+                    url,
+                    success: false,
+                    error_message: Some(e.to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+
+        for (pending, mut result) in batch.into_iter().zip(results) {
+            result.depth = Some(pending.depth);
+            result.parent_url = pending.parent_url;
+            self.pages_since_checkpoint += 1;
+
+            let status = if result.success { UrlStatus::Succeeded } else { UrlStatus::Failed };
+            self.statuses.insert(pending.url, status);
+
+            if result.success {
+                self.pages_crawled += 1;
+                // Links are discovered even when the page budget is spent, so that a resumed
+                // crawl with a larger budget can follow them
+                if pending.depth < self.config.max_depth {
+                    self.discover(&result, pending.depth + 1).await;
+                }
+            }
+            self.ready.push_back(result);
+        }
+    }
+
+    /// Queues the unseen links of `result` that pass the filter chain at `depth`.
+    async fn discover(&mut self, result: &CrawlResult, depth: usize) {
+        let Some(ref links) = result.links else { return };
+        // Relative links are relative to where the page ended up after redirects
+        let base = result.redirected_url.as_deref().unwrap_or(&result.url);
+
+        let mut groups = vec!["internal"];
+        if self.config.include_external {
+            groups.push("external");
+        }
+
+        let mut pending = Vec::new();
+        for link in groups.into_iter().filter_map(|g| links.get(g)).flatten() {
+            let Some(url) = link.href.as_deref().and_then(|href| normalize_url(href, Some(base))) else {
+                continue;
+            };
+            if !self.statuses.contains_key(&url) {
+                self.statuses.insert(url.clone(), UrlStatus::Queued);
+                self.depths.insert(url.clone(), depth);
+                let score = match self.config.url_scorer {
+                    Some(ref scorer) => scorer.score(&Link {
+                        href: Some(url.clone()),
+                        text: link.text.clone(),
+                        title: link.title.clone(),
+                    }),
+                    None => 0.0,
+                };
+                pending.push(self.pending(url, Some(result.url.clone()), depth, score));
+            }
+        }
+
+        // Rejected URLs keep a status so that they are not checked again
+        if let Some(ref chain) = self.config.filter_chain {
+            let passed: Vec<bool> = stream::iter(&pending)
+                .map(|p| chain.apply(&p.url))
+                .buffered(FILTER_CONCURRENCY)
+                .collect()
+                .await;
+            let mut accepted = Vec::new();
+            for (p, ok) in pending.into_iter().zip(passed) {
+                if ok {
+                    accepted.push(p);
+                } else {
+                    self.statuses.insert(p.url, UrlStatus::Filtered);
+                }
+            }
+            pending = accepted;
+        }
+        self.frontier.extend(pending);
+    }
+
+    fn pending(&mut self, url: String, parent_url: Option<String>, depth: usize, score: f64) -> PendingUrl {
+        self.next_seq += 1;
+        PendingUrl {
+            url,
+            parent_url,
+            depth,
+            score,
+            seq: self.next_seq,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("/docs/#intro", Some("https://Example.com/page")).as_deref(),
+            Some("https://example.com/docs")
+        );
+        assert_eq!(normalize_url("https://example.com:443/?", None).as_deref(), Some("https://example.com/"));
+        assert_eq!(
+            normalize_url("https://example.com/a?x=1#y", None).as_deref(),
+            Some("https://example.com/a?x=1")
+        );
+        assert!(normalize_url("mailto:team@example.com", None).is_none());
+        assert!(normalize_url("javascript:void(0)", Some("https://example.com/")).is_none());
+    }
+
+    fn pending(url: &str, depth: usize, score: f64, seq: u64) -> PendingUrl {
+        PendingUrl {
+            url: url.to_string(),
+            parent_url: None,
+            depth,
+            score,
+            seq,
+        }
+    }
+
+    fn drain(frontier: &mut Frontier) -> Vec<String> {
+        std::iter::from_fn(|| frontier.pop()).map(|p| p.url).collect()
+    }
+
+    #[test]
+    fn test_frontier_order() {
+        let page = || vec![pending("a", 1, 0.2, 1), pending("b", 1, 0.9, 2), pending("c", 2, 0.9, 3)];
+
+        let mut bfs = Frontier::new(DeepCrawlStrategy::Bfs);
+        bfs.extend(page());
+        assert_eq!(drain(&mut bfs), vec!["a", "b", "c"]);
+
+        let mut dfs = Frontier::new(DeepCrawlStrategy::Dfs);
+        dfs.extend(page());
+        assert_eq!(drain(&mut dfs), vec!["a", "b", "c"]);
+
+        let mut best = Frontier::new(DeepCrawlStrategy::BestFirst);
+        best.extend(page());
+        best.extend(vec![pending("d", 1, 0.9, 4)]);
+        assert_eq!(drain(&mut best), vec!["b", "d", "c", "a"]);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::models::Link;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+use url::Url;
+
+/// Scores a discovered link by how worth crawling it looks. Higher is better.
+///
+/// The scorers in this module return values between 0.0 and 1.0. Closures taking a
+/// [`Link`] implement this trait.
+pub trait UrlScorer: Send + Sync {
+    fn score(&self, link: &Link) -> f64;
+}
+
+impl<F> UrlScorer for F
+where
+    F: Fn(&Link) -> f64 + Send + Sync,
+{
+    fn score(&self, link: &Link) -> f64 {
+        self(link)
+    }
+}
+
+/// A serializable choice of scorer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UrlScorerConfig {
+    KeywordRelevance(KeywordRelevanceScorer),
+    PathDepth(PathDepthScorer),
+    Freshness(FreshnessScorer),
+    DomainAuthority(DomainAuthorityScorer),
+    Composite(CompositeScorer),
+    /// A scorer implemented outside this crate. It cannot be (de)serialized.
+    #[serde(skip)]
+    Custom(CustomScorer),
+}
+
+impl UrlScorerConfig {
+    /// Wraps a [`UrlScorer`] implementation, such as a closure.
+    pub fn custom(scorer: impl UrlScorer + 'static) -> Self {
+        UrlScorerConfig::Custom(CustomScorer(Arc::new(scorer)))
+    }
+}
+
+/// A user-provided [`UrlScorer`], shared between clones.
+#[derive(Clone)]
+pub struct CustomScorer(pub Arc<dyn UrlScorer>);
+
+impl fmt::Debug for CustomScorer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CustomScorer")
+    }
+}
+
+impl UrlScorer for UrlScorerConfig {
+    fn score(&self, link: &Link) -> f64 {
+        match self {
+            UrlScorerConfig::KeywordRelevance(s) => s.score(link),
+            UrlScorerConfig::PathDepth(s) => s.score(link),
+            UrlScorerConfig::Freshness(s) => s.score(link),
+            UrlScorerConfig::DomainAuthority(s) => s.score(link),
+            UrlScorerConfig::Composite(s) => s.score(link),
+            UrlScorerConfig::Custom(s) => s.0.score(link),
+        }
+    }
+}
+
+/// Scores links by the fraction of keywords found in their URL, text or title.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeywordRelevanceScorer {
+    /// Keywords describing the topic of the crawl.
+    pub keywords: Vec<String>,
+    /// Whether keywords are matched case-sensitively (default: false).
+    pub case_sensitive: bool,
+}
+
+impl KeywordRelevanceScorer {
+    /// Creates a new case-insensitive `KeywordRelevanceScorer`.
+    pub fn new(keywords: Vec<String>) -> Self {
+        Self {
+            keywords,
+            case_sensitive: false,
+        }
+    }
+}
+
+impl UrlScorer for KeywordRelevanceScorer {
+    fn score(&self, link: &Link) -> f64 {
+        if self.keywords.is_empty() {
+            return 0.0;
+        }
+
+        let haystack = [&link.href, &link.text, &link.title]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let fold = |s: &str| if self.case_sensitive { s.to_string() } else { s.to_lowercase() };
+        let haystack = fold(&haystack);
+
+        let matched = self
+            .keywords
+            .iter()
+            .filter(|k| !k.trim().is_empty() && haystack.contains(&fold(k.trim())))
+            .count();
+        matched as f64 / self.keywords.len() as f64
+    }
+}
+
+/// Scores links by how close the number of segments in their path is to `optimal_depth`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathDepthScorer {
+    /// Path depth that scores 1.0, e.g. 2 for `/docs/intro` (default: 3).
+    pub optimal_depth: usize,
+}
+
+impl Default for PathDepthScorer {
+    fn default() -> Self {
+        Self { optimal_depth: 3 }
+    }
+}
+
+impl UrlScorer for PathDepthScorer {
+    fn score(&self, link: &Link) -> f64 {
+        let Some(url) = link.href.as_deref().and_then(|href| Url::parse(href).ok()) else {
+            return 0.0;
+        };
+        let depth = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).count())
+            .unwrap_or(0);
+        1.0 / (1.0 + depth.abs_diff(self.optimal_depth) as f64)
+    }
+}
+
+/// Scores links by the most recent year found in their URL, such as `/2024/05/post`.
+///
+/// A link from `current_year` scores 1.0, losing 0.1 per year of age. Links without a
+/// year score 0.5 so that they rank between recent and old content.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreshnessScorer {
+    /// Year to measure age against (default: the current year).
+    pub current_year: Option<i32>,
+}
+
+impl UrlScorer for FreshnessScorer {
+    fn score(&self, link: &Link) -> f64 {
+        static YEAR: OnceLock<Regex> = OnceLock::new();
+        let year_re = YEAR.get_or_init(|| Regex::new(r"(?:^|[^0-9])((?:19|20)[0-9]{2})(?:[^0-9]|$)").unwrap());
+
+        let current_year = self.current_year.unwrap_or_else(current_year);
+        let Some(href) = link.href.as_deref() else { return 0.5 };
+        let latest = year_re
+            .captures_iter(href)
+            .filter_map(|c| c[1].parse::<i32>().ok())
+            .filter(|year| *year <= current_year)
+            .max();
+
+        match latest {
+            Some(year) => (1.0 - 0.1 * (current_year - year) as f64).max(0.0),
+            None => 0.5,
+        }
+    }
+}
+
+fn current_year() -> i32 {
+    // Formatted as "Sun, 06 Nov 1994 08:49:37 GMT"
+    httpdate::fmt_http_date(SystemTime::now())
+        .split_whitespace()
+        .nth(3)
+        .and_then(|year| year.parse().ok())
+        .unwrap_or(1970)
+}
+
+/// Scores links by a weight assigned to their domain. A domain also matches its
+/// subdomains, and the most specific match wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DomainAuthorityScorer {
+    /// Weights by domain, e.g. `{"docs.rs": 1.0}`.
+    pub domain_weights: HashMap<String, f64>,
+    /// Weight of domains that are not listed (default: 0.5).
+    pub default_weight: f64,
+}
+
+impl Default for DomainAuthorityScorer {
+    fn default() -> Self {
+        Self {
+            domain_weights: HashMap::new(),
+            default_weight: 0.5,
+        }
+    }
+}
+
+impl UrlScorer for DomainAuthorityScorer {
+    fn score(&self, link: &Link) -> f64 {
+        let host = link
+            .href
+            .as_deref()
+            .and_then(|href| Url::parse(href).ok())
+            .and_then(|url| url.host_str().map(|h| h.to_lowercase()));
+        let Some(host) = host else { return self.default_weight };
+
+        self.domain_weights
+            .iter()
+            .map(|(domain, weight)| (domain.trim().trim_start_matches('.').to_lowercase(), *weight))
+            .filter(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+            .max_by_key(|(domain, _)| domain.len())
+            .map(|(_, weight)| weight)
+            .unwrap_or(self.default_weight)
+    }
+}
+
+/// A scorer and its weight within a [`CompositeScorer`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedScorer {
+    pub scorer: UrlScorerConfig,
+    /// Relative weight of the scorer (default: 1.0).
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// Combines several scorers into their weighted average.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompositeScorer {
+    pub scorers: Vec<WeightedScorer>,
+}
+
+impl CompositeScorer {
+    /// Creates an empty `CompositeScorer`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `scorer` with the given weight.
+    pub fn with_scorer(mut self, scorer: UrlScorerConfig, weight: f64) -> Self {
+        self.scorers.push(WeightedScorer { scorer, weight });
+        self
+    }
+}
+
+impl UrlScorer for CompositeScorer {
+    fn score(&self, link: &Link) -> f64 {
+        let total_weight: f64 = self.scorers.iter().map(|s| s.weight).sum();
+        if total_weight <= 0.0 {
+            return 0.0;
+        }
+        self.scorers.iter().map(|s| s.weight * s.scorer.score(link)).sum::<f64>() / total_weight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(href: &str, text: Option<&str>) -> Link {
+        Link {
+            href: Some(href.to_string()),
+            text: text.map(str::to_string),
+            title: None,
+        }
+    }
+
+    #[test]
+    fn test_keyword_relevance() {
+        let scorer = KeywordRelevanceScorer::new(vec!["rust".to_string(), "async".to_string()]);
+
+        assert_eq!(scorer.score(&link("https://example.com/rust/async", None)), 1.0);
+        assert_eq!(scorer.score(&link("https://example.com/guide", Some("Learn Rust"))), 0.5);
+        assert_eq!(scorer.score(&link("https://example.com/python", None)), 0.0);
+    }
+
+    #[test]
+    fn test_path_depth() {
+        let scorer = PathDepthScorer { optimal_depth: 2 };
+
+        assert_eq!(scorer.score(&link("https://example.com/docs/intro/", None)), 1.0);
+        assert_eq!(scorer.score(&link("https://example.com/docs", None)), 0.5);
+        assert_eq!(scorer.score(&link("https://example.com/", None)), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_freshness() {
+        let scorer = FreshnessScorer {
+            current_year: Some(2025),
+        };
+
+        assert_eq!(scorer.score(&link("https://example.com/2025/01/post", None)), 1.0);
+        assert!((scorer.score(&link("https://example.com/blog/2020-03-01-post", None)) - 0.5).abs() < 1e-9);
+        assert_eq!(scorer.score(&link("https://example.com/1999/post", None)), 0.0);
+        assert_eq!(scorer.score(&link("https://example.com/about", None)), 0.5);
+        // Numbers that are part of longer ones are not years
+        assert_eq!(scorer.score(&link("https://example.com/item/120245", None)), 0.5);
+    }
+
+    #[test]
+    fn test_domain_authority() {
+        let scorer = DomainAuthorityScorer {
+            domain_weights: HashMap::from([("example.com".to_string(), 0.8), ("docs.example.com".to_string(), 1.0)]),
+            default_weight: 0.1,
+        };
+
+        assert_eq!(scorer.score(&link("https://docs.example.com/a", None)), 1.0);
+        assert_eq!(scorer.score(&link("https://blog.example.com/a", None)), 0.8);
+        assert_eq!(scorer.score(&link("https://other.org/", None)), 0.1);
+    }
+
+    #[test]
+    fn test_composite_from_json() {
+        let scorer: UrlScorerConfig = serde_json::from_value(serde_json::json!({
+            "type": "composite",
+            "scorers": [
+                {"scorer": {"type": "keyword_relevance", "keywords": ["rust"]}, "weight": 3.0},
+                {"scorer": {"type": "path_depth", "optimal_depth": 1}}
+            ]
+        }))
+        .unwrap();
+
+        // (3.0 * 1.0 + 1.0 * 0.5) / 4.0
+        assert_eq!(scorer.score(&link("https://example.com/rust/book", None)), 0.875);
+        assert_eq!(CompositeScorer::new().score(&link("https://example.com/", None)), 0.0);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::crawler::CrawlerError;
+use crate::models::DispatcherConfig;
+use anyhow::Result;
+use std::fs;
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Limits the number of pages crawled concurrently by `arun_many`.
+///
+/// Each page must hold a permit from the dispatcher while it is being crawled.
+/// In memory-adaptive mode, acquiring a permit additionally waits until system
+/// memory usage drops below the configured threshold.
+pub struct Dispatcher {
+    config: DispatcherConfig,
+    semaphore: Semaphore,
+}
+
+impl Default for Dispatcher {
+    fn default() -> Self {
+        Self::new(DispatcherConfig::default())
+    }
+}
+
+impl Dispatcher {
+    /// Creates a new `Dispatcher` from the given configuration.
+    pub fn new(config: DispatcherConfig) -> Self {
+        let permits = Self::permits_for(&config);
+        Self {
+            config,
+            semaphore: Semaphore::new(permits),
+        }
+    }
+
+    /// Returns the maximum number of pages crawled at once.
+    pub fn max_concurrent(&self) -> usize {
+        Self::permits_for(&self.config)
+    }
+
+    fn permits_for(config: &DispatcherConfig) -> usize {
+        match config {
+            DispatcherConfig::Semaphore { max_concurrent } => (*max_concurrent).max(1),
+            DispatcherConfig::MemoryAdaptive { max_concurrent, .. } => (*max_concurrent).max(1),
+        }
+    }
+
+    /// Waits for a free crawl slot.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>> {
+        let permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|e| CrawlerError::Other(e.into()))?;
+
+        if let DispatcherConfig::MemoryAdaptive {
+            memory_threshold_percent,
+            check_interval_ms,
+            memory_wait_timeout_ms,
+            ..
+        } = self.config
+        {
+            let start = Instant::now();
+            while let Some(usage) = memory_usage_percent() {
+                if usage < memory_threshold_percent {
+                    break;
+                }
+                if start.elapsed() > Duration::from_millis(memory_wait_timeout_ms) {
+                    return Err(CrawlerError::Timeout(format!(
+                        "memory usage to drop below {:.1}% (currently {:.1}%)",
+                        memory_threshold_percent, usage
+                    ))
+                    .into());
+                }
+                tokio::time::sleep(Duration::from_millis(check_interval_ms)).await;
+            }
+        }
+
+        Ok(permit)
+    }
+}
+
+/// Returns the current system memory usage as a percentage (0-100).
+///
+/// Reads `/proc/meminfo`, so this returns `None` on platforms without procfs.
+pub fn memory_usage_percent() -> Option<f64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    parse_meminfo(&meminfo)
+}
+
+fn parse_meminfo(meminfo: &str) -> Option<f64> {
+    let mut total = None;
+    let mut available = None;
+
+    for line in meminfo.lines() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("MemTotal:") => total = parts.next().and_then(|v| v.parse::<f64>().ok()),
+            Some("MemAvailable:") => available = parts.next().and_then(|v| v.parse::<f64>().ok()),
+            _ => {}
+        }
+    }
+
+    match (total, available) {
+        (Some(total), Some(available)) if total > 0.0 => Some((total - available) / total * 100.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_meminfo() {
+        let meminfo = "MemTotal:       16000000 kB\nMemFree:         1000000 kB\nMemAvailable:    4000000 kB\n";
+        let usage = parse_meminfo(meminfo).unwrap();
+        assert!((usage - 75.0).abs() < 1e-9);
+
+        assert!(parse_meminfo("MemTotal: 100 kB\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_semaphore_limits_concurrency() {
+        let dispatcher = Arc::new(Dispatcher::new(DispatcherConfig::Semaphore { max_concurrent: 2 }));
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..6)
+            .map(|_| {
+                let dispatcher = dispatcher.clone();
+                let active = active.clone();
+                let peak = peak.clone();
+                tokio::spawn(async move {
+                    let _permit = dispatcher.acquire().await.unwrap();
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_memory_adaptive_times_out_under_pressure() {
+        // A threshold of 0% can never be satisfied on a system that reports memory usage.
+        let dispatcher = Dispatcher::new(DispatcherConfig::MemoryAdaptive {
+            max_concurrent: 1,
+            memory_threshold_percent: 0.0,
+            check_interval_ms: 10,
+            memory_wait_timeout_ms: 30,
+        });
+
+        let result = dispatcher.acquire().await;
+        if memory_usage_percent().is_some() {
+            assert!(result.is_err());
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+}
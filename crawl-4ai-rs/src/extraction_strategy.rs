@@ -239,28 +239,26 @@ impl JsonXPathExtractionStrategy {
 
         let mut results = Vec::new();
 
-        if let Ok(val) = evaluate_xpath(&doc, &schema.base_selector) {
-            if let XPathValue::Nodeset(nodes) = val {
-                 for node in nodes.document_order() {
-                      let mut item = serde_json::Map::new();
-
-                      if let Some(base_fields) = &schema.base_fields {
-                          for field in base_fields {
-                              if let Some(val) = self.extract_single_field(node, field) {
-                                  item.insert(field.name.clone(), val);
-                              }
-                          }
-                      }
+        if let Ok(XPathValue::Nodeset(nodes)) = evaluate_xpath(&doc, &schema.base_selector) {
+            for node in nodes.document_order() {
+                let mut item = serde_json::Map::new();
 
-                      let field_data = self.extract_item(node, &schema.fields);
-                      if let Some(obj) = field_data.as_object() {
-                          for (k, v) in obj {
-                              item.insert(k.clone(), v.clone());
-                          }
-                      }
+                if let Some(base_fields) = &schema.base_fields {
+                    for field in base_fields {
+                        if let Some(val) = self.extract_single_field(node, field) {
+                            item.insert(field.name.clone(), val);
+                        }
+                    }
+                }
 
-                      results.push(Value::Object(item));
-                 }
+                let field_data = self.extract_item(node, &schema.fields);
+                if let Some(obj) = field_data.as_object() {
+                    for (k, v) in obj {
+                        item.insert(k.clone(), v.clone());
+                    }
+                }
+
+                results.push(Value::Object(item));
             }
         }
 
@@ -289,13 +287,11 @@ impl JsonXPathExtractionStrategy {
                       };
                       let context = Context::new();
 
-                      if let Ok(val) = xpath.evaluate(&context, node) {
-                          if let XPathValue::Nodeset(nodes) = val {
-                              if let Some(child) = nodes.document_order().first() {
-                                   if let Some(nested_fields) = &field.fields {
-                                       return self.extract_item(*child, nested_fields);
-                                   }
-                              }
+                      if let Ok(XPathValue::Nodeset(nodes)) = xpath.evaluate(&context, node) {
+                          if let Some(child) = nodes.document_order().first() {
+                               if let Some(nested_fields) = &field.fields {
+                                   return self.extract_item(*child, nested_fields);
+                               }
                           }
                       }
                  }
@@ -311,12 +307,10 @@ impl JsonXPathExtractionStrategy {
                       let context = Context::new();
 
                       let mut list = Vec::new();
-                       if let Ok(val) = xpath.evaluate(&context, node) {
-                          if let XPathValue::Nodeset(nodes) = val {
-                              for child in nodes.document_order() {
-                                  if let Some(nested_fields) = &field.fields {
-                                      list.push(self.extract_item(child, nested_fields));
-                                  }
+                      if let Ok(XPathValue::Nodeset(nodes)) = xpath.evaluate(&context, node) {
+                          for child in nodes.document_order() {
+                              if let Some(nested_fields) = &field.fields {
+                                  list.push(self.extract_item(child, nested_fields));
                               }
                           }
                       }
@@ -333,12 +327,8 @@ impl JsonXPathExtractionStrategy {
             let factory = Factory::new();
             if let Ok(Some(xpath)) = factory.build(selector) {
                 let context = Context::new();
-                if let Ok(val) = xpath.evaluate(&context, node) {
-                     if let XPathValue::Nodeset(nodes) = val {
-                         nodes.document_order().first().cloned()
-                     } else {
-                         None
-                     }
+                if let Ok(XPathValue::Nodeset(nodes)) = xpath.evaluate(&context, node) {
+                    nodes.document_order().first().cloned()
                 } else {
                     None
                 }
@@ -1,5 +1,18 @@
+pub mod adaptive_crawler;
 pub mod models;
+pub mod cache;
 pub mod crawler;
+pub mod crawler_strategy;
+pub mod deep_crawl;
+pub mod dispatcher;
+pub mod proxy_strategy;
+pub mod rate_limiter;
+pub mod request_interception;
+pub mod robots;
+pub mod url_seeder;
 pub mod markdown;
+pub mod metadata;
+pub mod storage_state;
 pub mod content_filter;
+pub mod content_scraping;
 pub mod extraction_strategy;
@@ -1,17 +1,22 @@
 use clap::{Parser, ValueEnum};
 use crawl_4ai_rs::crawler::AsyncWebCrawler;
-use crawl_4ai_rs::models::{CrawlerRunConfig, CrawlResult, ExtractionStrategyConfig};
+use crawl_4ai_rs::deep_crawl::checkpoint::CrawlCheckpoint;
+use crawl_4ai_rs::request_interception::BlockResources;
+use crawl_4ai_rs::storage_state::StorageState;
+use crawl_4ai_rs::models::{BrowserConfig, CrawlerRunConfig, CrawlerStrategy, HttpCrawlerConfig, CrawlResult, ExtractionStrategyConfig};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use url::Url;
 use anyhow::{Result, anyhow};
 use log::{info, error};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// The URL to crawl
-    #[arg(required = true)]
-    url: String,
+    /// The URL to crawl, a path to a local HTML file, or `-` to read HTML from stdin
+    #[arg(required_unless_present = "inspect_checkpoint")]
+    url: Option<String>,
 
     /// Output file path (optional)
     #[arg(short, long)]
@@ -25,9 +30,29 @@ struct Args {
     #[arg(long, default_value_t = false)]
     screenshot: bool,
 
+    /// Skip loading images, fonts, media and stylesheets
+    #[arg(long, default_value_t = false)]
+    block_resources: bool,
+
     /// Path to extraction strategy JSON config
     #[arg(long)]
     extraction_config: Option<PathBuf>,
+
+    /// Path to browser launch JSON config
+    #[arg(long)]
+    browser_config: Option<PathBuf>,
+
+    /// Start the browser with the cookies and web storage of a Playwright storage state JSON file
+    #[arg(long, value_name = "PATH")]
+    storage_state: Option<PathBuf>,
+
+    /// Fetch the page over plain HTTP instead of launching a browser
+    #[arg(long, default_value_t = false)]
+    http: bool,
+
+    /// Print the state saved in a deep crawl checkpoint file instead of crawling
+    #[arg(long, value_name = "PATH")]
+    inspect_checkpoint: Option<PathBuf>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
@@ -42,9 +67,32 @@ async fn main() -> Result<()> {
     env_logger::init();
     let args = Args::parse();
 
-    info!("Starting crawl for URL: {}", args.url);
+    if let Some(path) = &args.inspect_checkpoint {
+        return inspect_checkpoint(path, args.format).await;
+    }
+    let target = args.url.clone().unwrap_or_default();
+
+    info!("Starting crawl for URL: {}", target);
+
+    let mut browser_config = if let Some(path) = &args.browser_config {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read browser config: {}", e))?;
+        serde_json::from_str::<BrowserConfig>(&content)
+            .map_err(|e| anyhow!("Failed to parse browser config: {}", e))?
+    } else {
+        BrowserConfig::default()
+    };
+    if let Some(path) = &args.storage_state {
+        browser_config.storage_state = Some(StorageState::load(path)?);
+    }
+
+    let strategy = if args.http {
+        CrawlerStrategy::Http(HttpCrawlerConfig::default())
+    } else {
+        CrawlerStrategy::Browser
+    };
 
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::with_config(browser_config).with_strategy(strategy);
 
     let extraction_strategy = if let Some(path) = &args.extraction_config {
         let content = fs::read_to_string(path)
@@ -58,11 +106,13 @@ async fn main() -> Result<()> {
 
     let config = CrawlerRunConfig {
         screenshot: args.screenshot,
+        block_resources: args.block_resources.then(BlockResources::text_only),
         extraction_strategy,
         ..Default::default()
     };
 
-    let result = crawler.arun(&args.url, Some(config)).await;
+    let url = resolve_target(&target)?;
+    let result = crawler.arun(&url, Some(config)).await;
 
     match result {
         Ok(crawl_result) => {
@@ -79,6 +129,65 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Turns the target argument into something `arun` accepts: `-` becomes a `raw:` URL
+/// with the HTML read from stdin, and existing local paths become `file://` URLs.
+fn resolve_target(target: &str) -> Result<String> {
+    if target == "-" {
+        let mut html = String::new();
+        std::io::stdin()
+            .read_to_string(&mut html)
+            .map_err(|e| anyhow!("Failed to read HTML from stdin: {}", e))?;
+        return Ok(format!("raw:{}", html));
+    }
+
+    let path = Path::new(target);
+    if path.exists() {
+        let path = path
+            .canonicalize()
+            .map_err(|e| anyhow!("Failed to resolve {}: {}", target, e))?;
+        return Url::from_file_path(&path)
+            .map(|u| u.to_string())
+            .map_err(|_| anyhow!("Cannot convert {} to a file URL", path.display()));
+    }
+
+    Ok(target.to_string())
+}
+
+/// Prints a summary of a deep crawl checkpoint, or the whole state with `--format json`.
+async fn inspect_checkpoint(path: &Path, format: OutputFormat) -> Result<()> {
+    let checkpoint = CrawlCheckpoint::load(path).await?;
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&checkpoint)?);
+        return Ok(());
+    }
+
+    println!("Start URL: {}", checkpoint.start_url);
+    println!("Strategy: {:?}", checkpoint.strategy);
+    println!("Saved at: {}", checkpoint.saved_at);
+    println!("Pages crawled: {}", checkpoint.pages_crawled);
+    let counts: Vec<String> = checkpoint
+        .status_counts()
+        .iter()
+        .map(|(status, count)| format!("{:?}: {}", status, count))
+        .collect();
+    println!("URL statuses: {}", counts.join(", "));
+    println!("Max depth: {}", checkpoint.depths.values().max().copied().unwrap_or(0));
+
+    if checkpoint.is_finished() {
+        println!("Frontier: empty, the crawl finished");
+    } else {
+        println!("Frontier: {} URLs", checkpoint.frontier.len());
+        for pending in checkpoint.frontier.iter().take(10) {
+            println!("  [depth {}] {}", pending.depth, pending.url);
+        }
+        if checkpoint.frontier.len() > 10 {
+            println!("  ...");
+        }
+    }
+
+    Ok(())
+}
+
 fn handle_output(result: CrawlResult, args: &Args) -> Result<()> {
     let content = match args.format {
         OutputFormat::Markdown => result
@@ -1,9 +1,600 @@
 use crate::models::MarkdownGenerationResult;
 use crate::content_filter::ContentFilter;
-use html2text::from_read;
+use kuchiki::traits::*;
+use kuchiki::NodeRef;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use url::Url;
+
+/// Elements whose content never ends up in the markdown.
+const SKIPPED_TAGS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "iframe", "svg", "canvas", "object", "embed",
+];
+
+/// Elements rendered as separate blocks rather than inline with the surrounding text.
+const BLOCK_TAGS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "body", "dd", "details", "div", "dl", "dt",
+    "fieldset", "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6",
+    "header", "hr", "html", "li", "main", "nav", "ol", "p", "pre", "section", "summary", "table", "ul",
+];
+
+/// Options for converting HTML to Markdown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownOptions {
+    /// Wrap paragraphs at this many columns. `None` or `0` disables wrapping (default: None).
+    pub body_width: Option<usize>,
+    /// Render links as their text only, without the URL (default: false).
+    pub ignore_links: bool,
+    /// Leave images out of the markdown (default: false).
+    pub ignore_images: bool,
+    /// Escape backslashes and text that would otherwise start a list, heading or quote (default: true).
+    pub escape: bool,
+    /// Also escape every markdown special character in text, such as `*`, `_` and `[` (default: false).
+    pub escape_all: bool,
+    /// Render tables as GitHub-flavored markdown tables instead of plain rows (default: true).
+    pub gfm_tables: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            body_width: None,
+            ignore_links: false,
+            ignore_images: false,
+            escape: true,
+            escape_all: false,
+            gfm_tables: true,
+        }
+    }
+}
+
+/// Converts HTML documents to Markdown.
+///
+/// Headings, emphasis, links, images, lists, block quotes, tables and code are
+/// preserved. Code blocks become fenced blocks, tagged with the language found in a
+/// `language-x` or `lang-x` class on the `<pre>` or `<code>` element.
+#[derive(Debug, Clone, Default)]
+pub struct HtmlToMarkdown {
+    options: MarkdownOptions,
+}
+
+impl HtmlToMarkdown {
+    /// Creates a new converter with the given options.
+    pub fn new(options: MarkdownOptions) -> Self {
+        Self { options }
+    }
+
+    /// Converts `html` to Markdown.
+    pub fn convert(&self, html: &str) -> String {
+        let renderer = Renderer { options: &self.options, citations: None };
+        renderer.render_document(html)
+    }
+
+    /// Converts `html` to Markdown in which links are replaced by numbered citations.
+    ///
+    /// Returns the markdown and a `## References` section listing each distinct URL once,
+    /// with relative URLs resolved against `base_url`. Links become `text⟨1⟩` and images
+    /// `![alt⟨2⟩]`. The references are empty if the page has no links.
+    pub fn convert_with_citations(&self, html: &str, base_url: &str) -> (String, String) {
+        let renderer = Renderer {
+            options: &self.options,
+            citations: Some(RefCell::new(Citations::new(base_url))),
+        };
+        let markdown = renderer.render_document(html);
+        let references = renderer.citations.map(|c| c.into_inner().references_markdown()).unwrap_or_default();
+        (markdown, references)
+    }
+}
+
+/// Numbers the distinct URLs cited by a document in order of first appearance.
+struct Citations {
+    base_url: Option<Url>,
+    numbers: HashMap<String, usize>,
+    references: Vec<(String, String)>,
+}
+
+impl Citations {
+    fn new(base_url: &str) -> Self {
+        Self {
+            base_url: Url::parse(base_url).ok(),
+            numbers: HashMap::new(),
+            references: Vec::new(),
+        }
+    }
+
+    /// Returns the citation number of `href`, adding it to the references if it is new.
+    fn cite(&mut self, href: &str, title: Option<&str>, text: &str) -> usize {
+        let url = self
+            .base_url
+            .as_ref()
+            .and_then(|base| base.join(href).ok())
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| href.to_string());
+
+        if let Some(number) = self.numbers.get(&url) {
+            return *number;
+        }
+
+        let mut desc: Vec<&str> = title.into_iter().collect();
+        if !text.is_empty() && Some(text) != title {
+            desc.push(text);
+        }
+
+        let number = self.references.len() + 1;
+        self.numbers.insert(url.clone(), number);
+        self.references.push((url, desc.join(" - ")));
+        number
+    }
+
+    fn references_markdown(&self) -> String {
+        if self.references.is_empty() {
+            return String::new();
+        }
+        let mut out = String::from("## References\n\n");
+        for (i, (url, desc)) in self.references.iter().enumerate() {
+            if desc.is_empty() {
+                out.push_str(&format!("⟨{}⟩ {}\n", i + 1, url));
+            } else {
+                out.push_str(&format!("⟨{}⟩ {}: {}\n", i + 1, url, desc));
+            }
+        }
+        out
+    }
+}
+
+/// State of a single conversion.
+struct Renderer<'a> {
+    options: &'a MarkdownOptions,
+    citations: Option<RefCell<Citations>>,
+}
+
+impl Renderer<'_> {
+    fn render_document(&self, html: &str) -> String {
+        let document = kuchiki::parse_html().one(html);
+        let root = match document.select_first("body") {
+            Ok(body) => body.as_node().clone(),
+            Err(_) => document,
+        };
+        self.render_blocks(&root).trim().to_string()
+    }
+
+    /// Renders the children of `node` as a sequence of blocks separated by blank lines.
+    fn render_blocks(&self, node: &NodeRef) -> String {
+        let in_list_item = tag_name(node).as_deref() == Some("li");
+        let mut out = String::new();
+        let mut inline = String::new();
+
+        let push_block = |out: &mut String, block: String, tight: bool| {
+            if block.trim().is_empty() {
+                return;
+            }
+            if !out.is_empty() {
+                out.push_str(if tight { "\n" } else { "\n\n" });
+            }
+            out.push_str(&block);
+        };
+
+        for child in node.children() {
+            match tag_name(&child) {
+                Some(tag) if BLOCK_TAGS.contains(&tag.as_str()) => {
+                    push_block(&mut out, self.render_paragraph(&inline), false);
+                    inline.clear();
+                    // Nested lists hug the text of their list item
+                    let tight = in_list_item && (tag == "ul" || tag == "ol");
+                    push_block(&mut out, self.render_block(&child, &tag), tight);
+                }
+                _ => self.render_inline(&child, &mut inline),
+            }
+        }
+        push_block(&mut out, self.render_paragraph(&inline), false);
+
+        out
+    }
+
+    fn render_block(&self, node: &NodeRef, tag: &str) -> String {
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let mut text = String::new();
+                self.render_children_inline(node, &mut text);
+                let text = collapse_whitespace(&text);
+                if text.is_empty() {
+                    return String::new();
+                }
+                let level = tag[1..].parse::<usize>().unwrap_or(1);
+                format!("{} {}", "#".repeat(level), text)
+            }
+            "hr" => "---".to_string(),
+            "pre" => self.render_code_block(node),
+            "blockquote" => self
+                .render_blocks(node)
+                .lines()
+                .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "ul" | "ol" => self.render_list(node, tag == "ol"),
+            // A list item outside of a list
+            "li" => self.render_list_item(&self.render_blocks(node), "- "),
+            "table" => self.render_table(node),
+            _ => self.render_blocks(node),
+        }
+    }
+
+    /// Collapses whitespace in inline content, escapes it and wraps it to `body_width`.
+    fn render_paragraph(&self, inline: &str) -> String {
+        let lines: Vec<String> = inline.split('\n').map(collapse_whitespace).collect();
+        let start = lines.iter().position(|l| !l.is_empty()).unwrap_or(lines.len());
+        let end = lines.iter().rposition(|l| !l.is_empty()).map(|i| i + 1).unwrap_or(start);
+
+        let width = self.options.body_width.filter(|w| *w > 0);
+        let mut out: Vec<String> = Vec::new();
+        for (i, line) in lines[start..end].iter().enumerate() {
+            let wrapped = match width {
+                Some(width) => wrap(line, width),
+                None => vec![line.clone()],
+            };
+            for (j, mut physical) in wrapped.into_iter().enumerate() {
+                if self.options.escape {
+                    physical = escape_line_start(&physical);
+                }
+                // The line before a `<br>` ends in a hard line break
+                if i > 0 && j == 0 {
+                    if let Some(last) = out.last_mut() {
+                        last.push_str("  ");
+                    }
+                }
+                out.push(physical);
+            }
+        }
+        out.join("\n")
+    }
+
+    fn render_children_inline(&self, node: &NodeRef, out: &mut String) {
+        for child in node.children() {
+            self.render_inline(&child, out);
+        }
+    }
+
+    fn render_inline(&self, node: &NodeRef, out: &mut String) {
+        if let Some(text) = node.as_text() {
+            let text = text.borrow().replace(char::is_whitespace, " ");
+            out.push_str(&self.escape_text(&text));
+            return;
+        }
+
+        let Some(tag) = tag_name(node) else { return };
+        match tag.as_str() {
+            t if SKIPPED_TAGS.contains(&t) => {}
+            "br" => out.push('\n'),
+            "strong" | "b" => self.render_emphasis(node, "**", out),
+            "em" | "i" => self.render_emphasis(node, "*", out),
+            "del" | "s" | "strike" => self.render_emphasis(node, "~~", out),
+            "code" | "kbd" | "samp" | "tt" => {
+                let code = collapse_whitespace(&node.text_contents());
+                if !code.is_empty() {
+                    // Use a longer fence than any run of backticks inside the span
+                    let fence = "`".repeat(longest_run(&code, '`') + 1);
+                    let pad = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+                    out.push_str(&format!("{}{}{}{}{}", fence, pad, code, pad, fence));
+                }
+            }
+            "a" => self.render_link(node, out),
+            "img" => self.render_image(node, out),
+            _ => self.render_children_inline(node, out),
+        }
+    }
+
+    /// Wraps the content of `node` in `marker`, keeping surrounding whitespace outside of it.
+    fn render_emphasis(&self, node: &NodeRef, marker: &str, out: &mut String) {
+        let mut inner = String::new();
+        self.render_children_inline(node, &mut inner);
+
+        let trimmed = inner.trim();
+        if trimmed.is_empty() {
+            out.push_str(&inner);
+            return;
+        }
+        if inner.starts_with(char::is_whitespace) {
+            out.push(' ');
+        }
+        out.push_str(marker);
+        out.push_str(trimmed);
+        out.push_str(marker);
+        if inner.ends_with(char::is_whitespace) {
+            out.push(' ');
+        }
+    }
+
+    fn render_link(&self, node: &NodeRef, out: &mut String) {
+        let mut text = String::new();
+        self.render_children_inline(node, &mut text);
+
+        let non_empty = |name: &str| attribute(node, name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let title = non_empty("title");
+
+        let href = match non_empty("href") {
+            Some(href) if !self.options.ignore_links && !href.starts_with("javascript:") => href,
+            _ => {
+                out.push_str(&text);
+                return;
+            }
+        };
+
+        let label = collapse_whitespace(&text);
+        if label.is_empty() {
+            return;
+        }
+
+        if let Some(ref citations) = self.citations {
+            let number = citations.borrow_mut().cite(&href, title.as_deref(), &label);
+            out.push_str(&format!("{}⟨{}⟩", label, number));
+        } else if label == href && (href.starts_with("http://") || href.starts_with("https://")) {
+            out.push_str(&format!("<{}>", href));
+        } else {
+            out.push_str(&format!("[{}]({})", label, link_destination(&href, title.as_deref())));
+        }
+    }
+
+    fn render_image(&self, node: &NodeRef, out: &mut String) {
+        if self.options.ignore_images {
+            return;
+        }
+        let Some(element) = node.as_element() else { return };
+        let attrs = element.attributes.borrow();
+        let Some(src) = attrs.get("src").map(str::trim).filter(|s| !s.is_empty()) else { return };
+
+        let alt = collapse_whitespace(attrs.get("alt").unwrap_or_default());
+        let title = attrs.get("title").map(str::trim).filter(|t| !t.is_empty());
+        let alt = self.escape_text(&alt);
+        if let Some(ref citations) = self.citations {
+            let number = citations.borrow_mut().cite(src, title, &alt);
+            out.push_str(&format!("![{}⟨{}⟩]", alt, number));
+        } else {
+            out.push_str(&format!("![{}]({})", alt, link_destination(src, title)));
+        }
+    }
+
+    fn render_code_block(&self, node: &NodeRef) -> String {
+        let code = node
+            .children()
+            .find(|c| tag_name(c).as_deref() == Some("code"));
+
+        let language = code
+            .iter()
+            .chain(std::iter::once(node))
+            .find_map(code_language)
+            .unwrap_or_default();
+
+        let content = code.as_ref().unwrap_or(node).text_contents();
+        let content = content.strip_prefix('\n').unwrap_or(&content).trim_end();
+        let fence = "`".repeat(longest_run(content, '`').max(2) + 1);
+
+        format!("{}{}\n{}\n{}", fence, language, content, fence)
+    }
+
+    fn render_list(&self, node: &NodeRef, ordered: bool) -> String {
+        let mut number = attribute(node, "start")
+            .and_then(|s| s.trim().parse::<i64>().ok())
+            .unwrap_or(1);
+
+        let items: Vec<String> = node
+            .children()
+            .filter(|c| tag_name(c).as_deref() == Some("li"))
+            .map(|item| {
+                let marker = if ordered {
+                    let marker = format!("{}. ", number);
+                    number += 1;
+                    marker
+                } else {
+                    "- ".to_string()
+                };
+                self.render_list_item(&self.render_blocks(&item), &marker)
+            })
+            .collect();
+
+        // Items with several paragraphs make the whole list loose
+        let loose = items.iter().any(|item| item.contains("\n\n"));
+        items.join(if loose { "\n\n" } else { "\n" })
+    }
+
+    fn render_list_item(&self, content: &str, marker: &str) -> String {
+        let indent = " ".repeat(marker.len());
+        let mut lines = content.lines();
+        let mut out = format!("{}{}", marker, lines.next().unwrap_or_default());
+        for line in lines {
+            out.push('\n');
+            if !line.is_empty() {
+                out.push_str(&indent);
+                out.push_str(line);
+            }
+        }
+        out.trim_end().to_string()
+    }
+
+    fn render_table(&self, table: &NodeRef) -> String {
+        let mut caption = String::new();
+        let mut rows: Vec<Vec<String>> = Vec::new();
+
+        for child in table.children() {
+            match tag_name(&child).as_deref() {
+                Some("caption") => self.render_children_inline(&child, &mut caption),
+                Some("thead") | Some("tbody") | Some("tfoot") => {
+                    for row in child.children().filter(|r| tag_name(r).as_deref() == Some("tr")) {
+                        rows.push(self.render_table_row(&row));
+                    }
+                }
+                Some("tr") => rows.push(self.render_table_row(&child)),
+                _ => {}
+            }
+        }
+        rows.retain(|row| !row.is_empty());
+
+        let caption = collapse_whitespace(&caption);
+        let table_md = if rows.is_empty() {
+            String::new()
+        } else if self.options.gfm_tables {
+            let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+            let format_row = |row: &Vec<String>| {
+                let cells: Vec<&str> = (0..columns).map(|i| row.get(i).map(String::as_str).unwrap_or("")).collect();
+                format!("| {} |", cells.join(" | "))
+            };
+
+            let mut lines = vec![format_row(&rows[0]), format!("|{}", " --- |".repeat(columns))];
+            lines.extend(rows[1..].iter().map(format_row));
+            lines.join("\n")
+        } else {
+            rows.iter()
+                .map(|row| row.join(" | "))
+                .collect::<Vec<_>>()
+                .join("  \n")
+        };
+
+        [caption, table_md]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn render_table_row(&self, row: &NodeRef) -> Vec<String> {
+        let mut cells = Vec::new();
+        for cell in row.children() {
+            if !matches!(tag_name(&cell).as_deref(), Some("td") | Some("th")) {
+                continue;
+            }
+            let mut text = String::new();
+            self.render_children_inline(&cell, &mut text);
+            cells.push(collapse_whitespace(&text).replace('|', "\\|"));
+
+            // Keep columns aligned by padding cells that span several columns
+            let span = attribute(&cell, "colspan").and_then(|s| s.parse::<usize>().ok()).unwrap_or(1);
+            cells.extend(std::iter::repeat_n(String::new(), span.clamp(1, 1000) - 1));
+        }
+        cells
+    }
+
+    fn escape_text(&self, text: &str) -> String {
+        if !self.options.escape && !self.options.escape_all {
+            return text.to_string();
+        }
+        let specials: &[char] = if self.options.escape_all {
+            &['\\', '`', '*', '_', '{', '}', '[', ']', '(', ')', '#', '!']
+        } else {
+            &['\\']
+        };
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            if specials.contains(&c) {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out
+    }
+}
+
+fn tag_name(node: &NodeRef) -> Option<String> {
+    node.as_element().map(|e| e.name.local.to_string())
+}
+
+fn attribute(node: &NodeRef, name: &str) -> Option<String> {
+    node.as_element()?.attributes.borrow().get(name).map(str::to_string)
+}
+
+/// Returns the language named by a `language-x`, `lang-x` or `highlight-source-x` class.
+fn code_language(node: &NodeRef) -> Option<String> {
+    let class = attribute(node, "class")?;
+    class.split_whitespace().find_map(|c| {
+        c.strip_prefix("language-")
+            .or_else(|| c.strip_prefix("lang-"))
+            .or_else(|| c.strip_prefix("highlight-source-"))
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+    })
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in text.chars() {
+        if ch == c {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+
+/// Formats a link destination, wrapping URLs with spaces or parentheses in `<>`.
+fn link_destination(href: &str, title: Option<&str>) -> String {
+    let href = if href.contains([' ', '(', ')']) {
+        format!("<{}>", href)
+    } else {
+        href.to_string()
+    };
+    match title {
+        Some(title) => format!("{} \"{}\"", href, title.replace('"', "\\\"")),
+        None => href,
+    }
+}
+
+/// Escapes text at the start of a line that markdown would read as a list, heading or quote.
+fn escape_line_start(line: &str) -> String {
+    let bytes = line.as_bytes();
+    let followed_by_space = |i: usize| bytes.get(i).map(|b| *b == b' ').unwrap_or(true);
+
+    match bytes.first() {
+        Some(b'-') | Some(b'+') | Some(b'*') if followed_by_space(1) => format!("\\{}", line),
+        Some(b'>') => format!("\\{}", line),
+        Some(b'#') => {
+            let hashes = bytes.iter().take_while(|b| **b == b'#').count();
+            if hashes <= 6 && followed_by_space(hashes) {
+                format!("\\{}", line)
+            } else {
+                line.to_string()
+            }
+        }
+        Some(b'0'..=b'9') => {
+            let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+            if matches!(bytes.get(digits), Some(b'.') | Some(b')')) && followed_by_space(digits + 1) {
+                format!("{}\\{}", &line[..digits], &line[digits..])
+            } else {
+                line.to_string()
+            }
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// Greedily wraps `line` at `width` columns. Words longer than `width` get a line of their own.
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split(' ').filter(|w| !w.is_empty()) {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
 
 pub struct DefaultMarkdownGenerator {
     content_filter: Option<ContentFilter>,
+    options: MarkdownOptions,
 }
 
 impl Default for DefaultMarkdownGenerator {
@@ -14,26 +605,149 @@ impl Default for DefaultMarkdownGenerator {
 
 impl DefaultMarkdownGenerator {
     pub fn new(content_filter: Option<ContentFilter>) -> Self {
-        Self { content_filter }
+        Self {
+            content_filter,
+            options: MarkdownOptions::default(),
+        }
     }
 
-    pub async fn generate_markdown(&self, html: &str) -> MarkdownGenerationResult {
-        let raw_markdown = from_read(html.as_bytes(), 80); // 80 cols width
+    /// Converts HTML with `options` instead of the defaults.
+    pub fn with_options(mut self, options: MarkdownOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Converts `html` to markdown. Relative links in the citations are resolved against `base_url`.
+    pub async fn generate_markdown(&self, html: &str, base_url: &str) -> MarkdownGenerationResult {
+        let converter = HtmlToMarkdown::new(self.options.clone());
+        let raw_markdown = converter.convert(html);
+        let (markdown_with_citations, references_markdown) = converter.convert_with_citations(html, base_url);
 
         let (fit_markdown, fit_html) = if let Some(filter) = &self.content_filter {
             let filtered_html = filter.filter_content(html).await;
-            let filtered_markdown = from_read(filtered_html.as_bytes(), 80);
+            let filtered_markdown = converter.convert(&filtered_html);
             (Some(filtered_markdown), Some(filtered_html))
         } else {
             (None, None)
         };
 
         MarkdownGenerationResult {
-            raw_markdown: raw_markdown.clone(),
-            markdown_with_citations: raw_markdown.clone(),
-            references_markdown: String::new(),
+            raw_markdown,
+            markdown_with_citations,
+            references_markdown,
             fit_markdown,
             fit_html,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(html: &str) -> String {
+        HtmlToMarkdown::default().convert(html)
+    }
+
+    #[test]
+    fn test_headings_and_inline() {
+        let html = "<h1>Title</h1><p>Some <b>bold </b>and <em>italic</em> text with <code>x = 1</code>.<br>Next line</p><h3>Sub</h3>";
+        assert_eq!(
+            convert(html),
+            "# Title\n\nSome **bold** and *italic* text with `x = 1`.  \nNext line\n\n### Sub"
+        );
+    }
+
+    #[test]
+    fn test_links_and_images() {
+        let html = r#"<p><a href="/docs" title="Docs">Read the docs</a> or <a href="https://example.com">https://example.com</a>
+            <img src="/logo.png" alt="Logo"></p>"#;
+        assert_eq!(
+            convert(html),
+            "[Read the docs](/docs \"Docs\") or <https://example.com> ![Logo](/logo.png)"
+        );
+
+        let converter = HtmlToMarkdown::new(MarkdownOptions {
+            ignore_links: true,
+            ignore_images: true,
+            ..Default::default()
+        });
+        assert_eq!(converter.convert(html), "Read the docs or https://example.com");
+    }
+
+    #[test]
+    fn test_citations() {
+        let html = r#"<p>See <a href="/docs" title="Docs">the docs</a>, <a href="https://example.org/">Example</a>
+            and <a href="https://example.com/docs">the docs again</a>.</p><img src="logo.png" alt="Logo">"#;
+        let (markdown, references) = HtmlToMarkdown::default().convert_with_citations(html, "https://example.com/page");
+
+        assert_eq!(markdown, "See the docs⟨1⟩, Example⟨2⟩ and the docs again⟨1⟩.\n\n![Logo⟨3⟩]");
+        assert_eq!(
+            references,
+            "## References\n\n\
+             ⟨1⟩ https://example.com/docs: Docs - the docs\n\
+             ⟨2⟩ https://example.org/: Example\n\
+             ⟨3⟩ https://example.com/logo.png: Logo\n"
+        );
+
+        let (_, references) = HtmlToMarkdown::default().convert_with_citations("<p>No links</p>", "");
+        assert!(references.is_empty());
+    }
+
+    #[test]
+    fn test_lists_and_blockquote() {
+        let html = r#"
+            <ul><li>One</li><li>Two<ol start="3"><li>Three</li><li>Four</li></ol></li></ul>
+            <blockquote><p>Quoted</p><p>Twice</p></blockquote>
+        "#;
+        assert_eq!(
+            convert(html),
+            "- One\n- Two\n  3. Three\n  4. Four\n\n> Quoted\n>\n> Twice"
+        );
+    }
+
+    #[test]
+    fn test_fenced_code() {
+        let html = "<pre><code class=\"language-rust\">fn main() {\n    println!(\"hi\");\n}\n</code></pre>";
+        assert_eq!(convert(html), "```rust\nfn main() {\n    println!(\"hi\");\n}\n```");
+
+        let html = "<pre>plain ``` text</pre>";
+        assert_eq!(convert(html), "````\nplain ``` text\n````");
+    }
+
+    #[test]
+    fn test_tables() {
+        let html = r#"<table>
+            <thead><tr><th>Name</th><th>Price</th></tr></thead>
+            <tbody><tr><td>Widget</td><td>$1 | $2</td></tr><tr><td colspan="2">Total</td></tr></tbody>
+        </table>"#;
+        assert_eq!(
+            convert(html),
+            "| Name | Price |\n| --- | --- |\n| Widget | $1 \\| $2 |\n| Total |  |"
+        );
+
+        let converter = HtmlToMarkdown::new(MarkdownOptions { gfm_tables: false, ..Default::default() });
+        assert_eq!(converter.convert(html), "Name | Price  \nWidget | $1 \\| $2  \nTotal |");
+    }
+
+    #[test]
+    fn test_body_width_and_escaping() {
+        let converter = HtmlToMarkdown::new(MarkdownOptions { body_width: Some(10), ..Default::default() });
+        assert_eq!(
+            converter.convert("<p>Alpha beta - gamma delta</p>"),
+            "Alpha beta\n\\- gamma\ndelta"
+        );
+
+        assert_eq!(convert("<p># not a heading</p><p>2. not a list</p>"), "\\# not a heading\n\n2\\. not a list");
+        assert_eq!(convert("<p>snake_case *stars*</p>"), "snake_case *stars*");
+
+        let converter = HtmlToMarkdown::new(MarkdownOptions { escape_all: true, ..Default::default() });
+        assert_eq!(converter.convert("<p>snake_case *stars*</p>"), "snake\\_case \\*stars\\*");
+    }
+
+    #[test]
+    fn test_skips_scripts_and_styles() {
+        let html = "<html><head><title>T</title><style>p {}</style></head><body><script>var x;</script><p>Visible</p></body></html>";
+        assert_eq!(convert(html), "Visible");
+    }
+}
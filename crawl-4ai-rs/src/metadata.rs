@@ -0,0 +1,148 @@
+use crate::models::PageMetadata;
+use kuchiki::traits::*;
+use kuchiki::NodeRef;
+use url::Url;
+
+/// Meta tags holding the publication date, in order of preference.
+const PUBLISHED_KEYS: &[&str] = &["article:published_time", "datepublished", "pubdate", "publish_date", "date", "dc.date"];
+
+/// Meta tags holding the modification date, in order of preference.
+const MODIFIED_KEYS: &[&str] = &["article:modified_time", "datemodified", "og:updated_time", "last-modified"];
+
+/// Extracts the metadata of the page at `page_url` from its HTML.
+pub fn extract_metadata(html: &str, page_url: &str) -> PageMetadata {
+    let document = kuchiki::parse_html().one(html);
+    let page = Url::parse(page_url).ok();
+    let resolve = |href: &str| match page {
+        Some(ref page) => page.join(href).map(String::from).unwrap_or_else(|_| href.to_string()),
+        None => href.to_string(),
+    };
+
+    // Meta tags keyed by their lowercased `name`, `property` or `itemprop`, in document order
+    let mut metas: Vec<(String, String)> = Vec::new();
+    if let Ok(nodes) = document.select("meta[content]") {
+        for node in nodes {
+            let attrs = node.attributes.borrow();
+            let Some(key) = attrs.get("name").or_else(|| attrs.get("property")).or_else(|| attrs.get("itemprop")) else {
+                continue;
+            };
+            let content = attrs.get("content").unwrap_or_default().trim();
+            if !content.is_empty() {
+                metas.push((key.trim().to_lowercase(), content.to_string()));
+            }
+        }
+    }
+    let meta = |key: &str| metas.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+    let first_meta = |keys: &[&str]| keys.iter().find_map(|key| meta(key));
+
+    let mut metadata = PageMetadata {
+        title: document
+            .select_first("title")
+            .ok()
+            .map(|t| t.text_contents().trim().to_string())
+            .filter(|t| !t.is_empty()),
+        description: meta("description"),
+        keywords: meta("keywords")
+            .map(|k| k.split(',').map(str::trim).filter(|k| !k.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default(),
+        canonical_url: link_href(&document, "link[rel~=canonical][href]").map(|h| resolve(&h)),
+        language: document
+            .select_first("html[lang]")
+            .ok()
+            .and_then(|html| html.attributes.borrow().get("lang").map(|l| l.trim().to_string()))
+            .filter(|l| !l.is_empty()),
+        author: first_meta(&["author", "article:author"]),
+        published_time: first_meta(PUBLISHED_KEYS),
+        modified_time: first_meta(MODIFIED_KEYS),
+        favicon: link_href(&document, "link[rel~=icon][href], link[rel~=apple-touch-icon][href]").map(|h| resolve(&h)),
+        ..Default::default()
+    };
+
+    for (key, value) in &metas {
+        let group = if key.starts_with("og:") {
+            &mut metadata.open_graph
+        } else if key.starts_with("twitter:") {
+            &mut metadata.twitter
+        } else {
+            continue;
+        };
+        group.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+
+    if let Ok(scripts) = document.select(r#"script[type="application/ld+json"]"#) {
+        for script in scripts {
+            match serde_json::from_str(script.text_contents().trim()) {
+                Ok(value) => metadata.json_ld.push(value),
+                Err(e) => eprintln!("Ignoring invalid JSON-LD on {}: {}", page_url, e),
+            }
+        }
+    }
+
+    let json_ld_field = |field: &str| {
+        metadata
+            .json_ld
+            .iter()
+            .find_map(|value| value.get(field).and_then(|v| v.as_str()).map(str::to_string))
+    };
+    if metadata.published_time.is_none() {
+        metadata.published_time = json_ld_field("datePublished");
+    }
+    if metadata.modified_time.is_none() {
+        metadata.modified_time = json_ld_field("dateModified");
+    }
+
+    metadata
+}
+
+fn link_href(document: &NodeRef, selector: &str) -> Option<String> {
+    let node = document.select_first(selector).ok()?;
+    let href = node.attributes.borrow().get("href")?.trim().to_string();
+    (!href.is_empty()).then_some(href)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_metadata() {
+        let html = r#"<html lang="en-GB"><head>
+            <title> Async in Rust </title>
+            <meta name="Description" content="A guide to async.">
+            <meta name="keywords" content="rust, async, ,tokio">
+            <meta name="author" content="Jane Doe">
+            <meta property="article:published_time" content="2024-05-01T10:00:00Z">
+            <meta property="og:title" content="Async in Rust">
+            <meta property="og:image" content="https://example.com/a.png">
+            <meta property="og:image" content="https://example.com/b.png">
+            <meta name="twitter:card" content="summary">
+            <link rel="canonical" href="/guides/async">
+            <link rel="shortcut icon" href="/favicon.png">
+            <script type="application/ld+json">{"@type": "Article", "dateModified": "2024-06-01"}</script>
+            <script type="application/ld+json">{not json}</script>
+        </head><body></body></html>"#;
+
+        let metadata = extract_metadata(html, "https://example.com/blog/post?id=1");
+
+        assert_eq!(metadata.title.as_deref(), Some("Async in Rust"));
+        assert_eq!(metadata.description.as_deref(), Some("A guide to async."));
+        assert_eq!(metadata.keywords, vec!["rust", "async", "tokio"]);
+        assert_eq!(metadata.language.as_deref(), Some("en-GB"));
+        assert_eq!(metadata.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(metadata.canonical_url.as_deref(), Some("https://example.com/guides/async"));
+        assert_eq!(metadata.favicon.as_deref(), Some("https://example.com/favicon.png"));
+        assert_eq!(metadata.published_time.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert_eq!(metadata.modified_time.as_deref(), Some("2024-06-01"));
+        assert_eq!(metadata.open_graph["og:image"], "https://example.com/a.png");
+        assert_eq!(metadata.open_graph.len(), 2);
+        assert_eq!(metadata.twitter["twitter:card"], "summary");
+        assert_eq!(metadata.json_ld, vec![json!({"@type": "Article", "dateModified": "2024-06-01"})]);
+    }
+
+    #[test]
+    fn test_extract_metadata_from_bare_page() {
+        let metadata = extract_metadata("<p>No head</p>", "raw:");
+        assert_eq!(metadata, PageMetadata::default());
+    }
+}
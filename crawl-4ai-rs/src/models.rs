@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use crate::content_filter::ContentFilter;
+use crate::content_scraping::ContentScrapingStrategy;
+use crate::markdown::MarkdownOptions;
+use crate::proxy_strategy::ProxyConfig;
+use crate::request_interception::BlockResources;
+use crate::storage_state::{Cookie, StorageState};
 use crate::extraction_strategy::{JsonCssExtractionStrategy, JsonXPathExtractionStrategy, RegexExtractionStrategy};
 
 /// Strategy to wait for content to load before extracting it.
@@ -35,20 +41,222 @@ pub enum ExtractionStrategyConfig {
     Regex(RegexExtractionStrategy),
 }
 
+/// Which HTML of a page markdown is generated from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkdownSource {
+    /// The HTML of the page as loaded (default).
+    #[default]
+    RawHtml,
+    /// The output of the content scraping strategy.
+    CleanedHtml,
+}
+
+/// Controls how a crawl run uses the local result cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheMode {
+    /// Read cached results and write fresh ones.
+    Enabled,
+    /// Skip the cache for this run (default).
+    #[default]
+    Bypass,
+    /// Only read cached results, never write.
+    ReadOnly,
+    /// Only write fresh results, never read.
+    WriteOnly,
+    /// Do not use the cache at all.
+    Disabled,
+}
+
+impl CacheMode {
+    /// Returns whether cached results may be returned.
+    pub fn should_read(&self) -> bool {
+        matches!(self, CacheMode::Enabled | CacheMode::ReadOnly)
+    }
+
+    /// Returns whether fresh results should be stored.
+    pub fn should_write(&self) -> bool {
+        matches!(self, CacheMode::Enabled | CacheMode::WriteOnly)
+    }
+}
+
+/// Strategy used by `arun_many` to decide how many pages are crawled at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DispatcherConfig {
+    /// Crawl at most `max_concurrent` pages at a time.
+    Semaphore {
+        max_concurrent: usize,
+    },
+    /// Crawl at most `max_concurrent` pages at a time, and hold back new pages
+    /// while system memory usage is above `memory_threshold_percent`.
+    MemoryAdaptive {
+        max_concurrent: usize,
+        /// Memory usage (0-100) above which new pages are delayed (default: 90.0).
+        memory_threshold_percent: f64,
+        /// Interval in milliseconds between memory checks (default: 1000ms).
+        check_interval_ms: u64,
+        /// Maximum time in milliseconds a page waits for memory to free up (default: 600000ms).
+        memory_wait_timeout_ms: u64,
+    },
+}
+
+impl Default for DispatcherConfig {
+    fn default() -> Self {
+        DispatcherConfig::MemoryAdaptive {
+            max_concurrent: 20,
+            memory_threshold_percent: 90.0,
+            check_interval_ms: 1000,
+            memory_wait_timeout_ms: 600_000,
+        }
+    }
+}
+
+/// How `AsyncWebCrawler` fetches pages.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CrawlerStrategy {
+    /// Render pages in Chromium (default).
+    #[default]
+    Browser,
+    /// Fetch pages over plain HTTP without launching a browser or running JavaScript.
+    Http(HttpCrawlerConfig),
+}
+
+/// Configuration for the plain HTTP crawler strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpCrawlerConfig {
+    /// Request timeout in milliseconds (default: 30000ms). `CrawlerRunConfig::page_timeout` takes precedence.
+    pub timeout_ms: u64,
+    /// Maximum number of redirects to follow (default: 10).
+    pub max_redirects: usize,
+    /// Additional headers sent with every request. `Authorization`, `Cookie` and
+    /// `Proxy-Authorization` are dropped once a redirect leaves the origin of the crawled URL.
+    pub headers: HashMap<String, String>,
+    /// Largest response body read, in bytes. Larger pages fail to crawl (default: 10 MB).
+    pub max_body_bytes: usize,
+}
+
+impl Default for HttpCrawlerConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 30_000,
+            max_redirects: 10,
+            headers: HashMap::new(),
+            max_body_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// Configuration for launching the browser used by `AsyncWebCrawler`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrowserConfig {
+    /// Whether to run the browser without a visible window (default: true).
+    pub headless: bool,
+    /// Whether to keep Chrome's sandbox enabled (default: false, as most containers lack the privileges for it).
+    pub sandbox: bool,
+    /// Viewport width in pixels (default: 1080).
+    pub viewport_width: u32,
+    /// Viewport height in pixels (default: 600).
+    pub viewport_height: u32,
+    /// Custom user agent string (optional).
+    pub user_agent: Option<String>,
+    /// Additional command line arguments passed to Chrome.
+    pub extra_args: Vec<String>,
+    /// Browser locale, e.g. "en-US" (optional).
+    pub locale: Option<String>,
+    /// IANA timezone identifier, e.g. "Europe/Berlin" (optional).
+    pub timezone: Option<String>,
+    /// Path to the Chrome/Chromium executable (optional).
+    /// Falls back to `CHROME_EXECUTABLE`, then to auto-detection.
+    pub executable_path: Option<PathBuf>,
+    /// Directory for a persistent browser profile (optional).
+    pub user_data_dir: Option<PathBuf>,
+    /// Cookies set in every browser context, in Playwright's format (default: none).
+    pub cookies: Vec<Cookie>,
+    /// Cookies and web storage to start every browser context with, e.g. a logged-in
+    /// state saved by `AsyncWebCrawler::export_storage_state` (optional).
+    pub storage_state: Option<StorageState>,
+    /// Proxy for all traffic of the browser and of the HTTP strategy (optional).
+    pub proxy: Option<ProxyConfig>,
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            headless: true,
+            sandbox: false,
+            viewport_width: 1080,
+            viewport_height: 600,
+            user_agent: None,
+            extra_args: Vec::new(),
+            locale: None,
+            timezone: None,
+            executable_path: None,
+            user_data_dir: None,
+            cookies: Vec::new(),
+            storage_state: None,
+            proxy: None,
+        }
+    }
+}
+
 /// Configuration for a crawler run.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CrawlerRunConfig {
-    /// Optional session ID for persistent browser contexts.
+    /// Optional session ID. Crawls with the same ID share a browser context and reuse its
+    /// page, so cookies and page state carry over between them.
     pub session_id: Option<String>,
+    /// JavaScript snippets run in order after navigation and before `wait_for`, e.g. to click
+    /// "load more" or dismiss a dialog (default: none). Return values, including resolved
+    /// promises, are collected into `CrawlResult::js_execution_result`.
+    #[serde(default)]
+    pub js_code: Vec<String>,
+    /// Whether to run `js_code` on the session's open page without navigating again (default: false).
+    ///
+    /// Needs a `session_id` whose page was opened by an earlier crawl.
+    #[serde(default)]
+    pub js_only: bool,
     /// Strategy to wait for content loading.
     pub wait_for: Option<WaitStrategy>,
     /// Content filter to use for processing HTML.
     pub content_filter: Option<ContentFilter>,
+    /// Options for converting HTML to markdown (optional).
+    #[serde(default)]
+    pub markdown_options: Option<MarkdownOptions>,
+    /// Strategy producing `cleaned_html` (default: `ContentScrapingStrategy::default()`).
+    #[serde(default)]
+    pub scraping_strategy: Option<ContentScrapingStrategy>,
+    /// HTML that markdown is generated from (default: raw HTML).
+    #[serde(default)]
+    pub markdown_source: MarkdownSource,
+    /// CSS selector limiting the result to the matching elements (optional).
+    ///
+    /// Cleaned HTML, markdown, extraction, links and media all come from the matches only,
+    /// while `html` keeps the whole page.
+    pub css_selector: Option<String>,
+    /// CSS selectors of the elements that markdown and extraction are built from (default: none).
+    ///
+    /// Unlike `css_selector`, links and media still come from the whole page.
+    #[serde(default)]
+    pub target_elements: Vec<String>,
     /// Extraction strategy to use.
     pub extraction_strategy: Option<ExtractionStrategyConfig>,
     /// Whether to take a screenshot of the page.
     #[serde(default)]
     pub screenshot: bool,
+    /// Requests the page should not make, e.g. images and ad domains (optional).
+    ///
+    /// Only applies on the browser, where blocked requests fail as if blocked by the client.
+    pub block_resources: Option<BlockResources>,
+    /// Proxy for this run, overriding the browser proxy and the crawler's proxy rotation (optional).
+    ///
+    /// On the browser this creates a browser context using the proxy; a session keeps the
+    /// proxy its context was created with.
+    pub proxy: Option<ProxyConfig>,
     /// Timeout for page navigation in milliseconds.
     pub page_timeout: Option<u64>,
     /// Timeout for the wait strategy in milliseconds (default: 10000ms).
@@ -56,6 +264,12 @@ pub struct CrawlerRunConfig {
     /// Whether to retry on 404 errors (default: false).
     #[serde(default)]
     pub retry_404: bool,
+    /// Whether to respect the site's robots.txt (default: false).
+    #[serde(default)]
+    pub check_robots_txt: bool,
+    /// How this run uses the local result cache (default: bypass).
+    #[serde(default)]
+    pub cache_mode: CacheMode,
 }
 
 /// Result of a crawl operation.
@@ -88,6 +302,40 @@ pub struct CrawlResult {
     /// Error message if the crawl failed (optional).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+    /// Number of links followed from the start page of a deep crawl (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<usize>,
+    /// URL of the page this page was discovered on during a deep crawl, as crawled before any redirect (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_url: Option<String>,
+    /// Metadata from the `<head>` of the page (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<PageMetadata>,
+    /// HTTP status code of the final response, e.g. 200, 203 or 304 (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    /// Headers of the final response, keyed by lowercased name (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_headers: Option<HashMap<String, String>>,
+    /// URL the page was served from after following redirects (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirected_url: Option<String>,
+    /// Redirects followed before reaching `redirected_url`, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirect_chain: Vec<Redirect>,
+    /// Return values of `CrawlerRunConfig::js_code`, in order. Scripts that threw are
+    /// recorded as `{"error": message}` (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub js_execution_result: Option<Vec<serde_json::Value>>,
+}
+
+/// A redirect response followed while fetching a page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Redirect {
+    /// URL that answered with the redirect.
+    pub url: String,
+    /// Status code of the redirect response, e.g. 301 or 302.
+    pub status_code: u16,
 }
 
 /// Result of markdown generation.
@@ -107,6 +355,37 @@ pub struct MarkdownGenerationResult {
     pub fit_html: Option<String>,
 }
 
+/// Metadata of a page, read from its `<html>` and `<head>` elements.
+///
+/// URLs are resolved against the page URL.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PageMetadata {
+    /// Content of the `<title>` element.
+    pub title: Option<String>,
+    /// `<meta name="description">`.
+    pub description: Option<String>,
+    /// Comma-separated entries of `<meta name="keywords">`.
+    pub keywords: Vec<String>,
+    /// `<link rel="canonical">`.
+    pub canonical_url: Option<String>,
+    /// `lang` attribute of the `<html>` element.
+    pub language: Option<String>,
+    /// `<meta name="author">` or `article:author`.
+    pub author: Option<String>,
+    /// Publication date from `article:published_time`, similar meta tags or JSON-LD `datePublished`.
+    pub published_time: Option<String>,
+    /// Modification date from `article:modified_time`, similar meta tags or JSON-LD `dateModified`.
+    pub modified_time: Option<String>,
+    /// Open Graph tags keyed by property, e.g. `og:title`. The first of repeated tags is kept.
+    pub open_graph: HashMap<String, String>,
+    /// Twitter card tags keyed by name, e.g. `twitter:card`.
+    pub twitter: HashMap<String, String>,
+    /// First `<link rel="icon">`, `shortcut icon` or `apple-touch-icon`.
+    pub favicon: Option<String>,
+    /// Every valid `<script type="application/ld+json">` block.
+    pub json_ld: Vec<serde_json::Value>,
+}
+
 /// Represents a media item found on the page (e.g., an image).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaItem {
@@ -0,0 +1,273 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use url::Url;
+
+/// Smallest delay doubled on backoff, so that a zero base delay still backs off.
+const MIN_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Throttles requests per domain and backs off when a server signals rate limiting.
+///
+/// Every request to a domain waits for a random delay within `base_delay_ms` since the
+/// previous request to that domain. When a response status is one of `rate_limit_codes`,
+/// the delay for that domain doubles (with jitter) up to `max_delay_ms`, or follows the
+/// server's `Retry-After` header when present. Clones share the same per-domain state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimiter {
+    /// Range (min, max) in milliseconds of the delay between two requests to the same domain.
+    pub base_delay_ms: (u64, u64),
+    /// Upper bound in milliseconds for the backoff delay.
+    pub max_delay_ms: u64,
+    /// Number of consecutive rate-limited responses tolerated per domain before giving up.
+    pub max_retries: u32,
+    /// HTTP status codes that trigger a backoff.
+    pub rate_limit_codes: Vec<i64>,
+    #[serde(skip)]
+    domains: Arc<Mutex<HashMap<String, DomainState>>>,
+}
+
+#[derive(Debug, Clone)]
+struct DomainState {
+    next_allowed: Instant,
+    current_delay: Duration,
+    fail_count: u32,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new((1000, 3000), 60_000, 3)
+    }
+}
+
+impl RateLimiter {
+    /// Creates a new `RateLimiter` that backs off on 429 and 503 responses.
+    pub fn new(base_delay_ms: (u64, u64), max_delay_ms: u64, max_retries: u32) -> Self {
+        Self {
+            base_delay_ms,
+            max_delay_ms,
+            max_retries,
+            rate_limit_codes: vec![429, 503],
+            domains: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns whether `status_code` is treated as a rate-limit signal.
+    pub fn is_rate_limit_code(&self, status_code: i64) -> bool {
+        self.rate_limit_codes.contains(&status_code)
+    }
+
+    /// Waits until a request to the domain of `url` is allowed.
+    pub async fn wait_if_needed(&self, url: &str) {
+        let wait = self.reserve_slot(url);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Reserves the next request slot for the domain of `url` and returns how long to wait for it.
+    ///
+    /// The slot is reserved before sleeping so that concurrent requests to the same domain
+    /// queue up behind each other.
+    fn reserve_slot(&self, url: &str) -> Duration {
+        let mut domains = self.domains.lock().unwrap();
+        let now = Instant::now();
+
+        match domains.get_mut(&Self::domain(url)) {
+            Some(state) => {
+                // A domain backing off (or still easing off) keeps its delay; otherwise every
+                // request gets its own random delay so the spacing does not look mechanical.
+                let backing_off =
+                    state.fail_count > 0 || state.current_delay > Duration::from_millis(self.base_delay_ms.1);
+                let delay = if backing_off { state.current_delay } else { self.random_base_delay() };
+                let start = state.next_allowed.max(now);
+                state.next_allowed = start + delay;
+                start - now
+            }
+            None => {
+                let current_delay = self.random_base_delay();
+                domains.insert(Self::domain(url), DomainState {
+                    next_allowed: now + current_delay,
+                    current_delay,
+                    fail_count: 0,
+                });
+                Duration::ZERO
+            }
+        }
+    }
+
+    /// Updates the delay for the domain of `url` after receiving `status_code`.
+    ///
+    /// Returns `false` once the domain has exceeded `max_retries` consecutive
+    /// rate-limited responses, `true` otherwise.
+    pub fn update_delay(&self, url: &str, status_code: i64, retry_after: Option<Duration>) -> bool {
+        let mut domains = self.domains.lock().unwrap();
+        let base_delay = self.random_base_delay();
+        let state = domains.entry(Self::domain(url)).or_insert_with(|| DomainState {
+            next_allowed: Instant::now(),
+            current_delay: base_delay,
+            fail_count: 0,
+        });
+
+        if self.is_rate_limit_code(status_code) {
+            state.fail_count += 1;
+            if state.fail_count > self.max_retries {
+                return false;
+            }
+
+            let jitter = rand::thread_rng().gen_range(0.75..1.25);
+            // A zero Retry-After (e.g. a date in the past due to clock skew) backs off normally,
+            // and a short one is not allowed to undercut the base delay.
+            let backoff = match retry_after {
+                Some(retry_after) if !retry_after.is_zero() => {
+                    retry_after.max(Duration::from_millis(self.base_delay_ms.0)).max(MIN_BACKOFF)
+                }
+                _ => state.current_delay.max(MIN_BACKOFF).mul_f64(2.0 * jitter),
+            };
+            state.current_delay = backoff.min(Duration::from_millis(self.max_delay_ms));
+            state.next_allowed = Instant::now() + state.current_delay;
+        } else {
+            // Ease off gradually so a recovering server is not hit at full speed right away.
+            state.current_delay = base_delay.max(state.current_delay.mul_f64(0.75));
+            state.fail_count = 0;
+        }
+
+        true
+    }
+
+    /// Returns whether the domain of `url` has used up its rate-limit retries.
+    pub fn retries_exhausted(&self, url: &str) -> bool {
+        let domains = self.domains.lock().unwrap();
+        domains
+            .get(&Self::domain(url))
+            .map(|state| state.fail_count > self.max_retries)
+            .unwrap_or(false)
+    }
+
+    /// Returns the current delay between requests to the domain of `url`, if it has been seen.
+    pub fn current_delay(&self, url: &str) -> Option<Duration> {
+        let domains = self.domains.lock().unwrap();
+        domains.get(&Self::domain(url)).map(|state| state.current_delay)
+    }
+
+    fn random_base_delay(&self) -> Duration {
+        let (min, max) = self.base_delay_ms;
+        let ms = if max > min {
+            rand::thread_rng().gen_range(min..=max)
+        } else {
+            min
+        };
+        Duration::from_millis(ms)
+    }
+
+    fn domain(url: &str) -> String {
+        Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+            .unwrap_or_else(|| url.to_string())
+    }
+}
+
+/// Parses a `Retry-After` header value, given either in seconds or as an HTTP date.
+///
+/// A date in the past gives `Duration::ZERO`, which [`RateLimiter::update_delay`] treats
+/// like a missing header.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_backoff_and_recovery() {
+        let limiter = RateLimiter::new((100, 100), 1000, 2);
+        let url = "https://example.com/page";
+
+        assert!(limiter.update_delay(url, 429, None));
+        let first = limiter.current_delay(url).unwrap();
+        assert!(first >= Duration::from_millis(150) && first <= Duration::from_millis(250));
+
+        assert!(limiter.update_delay(url, 503, Some(Duration::from_secs(30))));
+        assert_eq!(limiter.current_delay(url), Some(Duration::from_millis(1000)), "Retry-After is capped");
+
+        assert!(!limiter.update_delay(url, 429, None), "Third failure exceeds max_retries");
+        assert!(limiter.retries_exhausted(url));
+
+        assert!(limiter.update_delay(url, 200, None));
+        assert!(!limiter.retries_exhausted(url));
+        assert_eq!(limiter.current_delay(url), Some(Duration::from_millis(750)));
+    }
+
+    #[test]
+    fn test_short_retry_after_still_backs_off() {
+        let limiter = RateLimiter::new((200, 200), 10_000, 5);
+        let url = "https://example.com/page";
+
+        // A Retry-After date in the past uses the exponential backoff
+        assert!(limiter.update_delay(url, 429, parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT")));
+        assert!(limiter.current_delay(url).unwrap() >= Duration::from_millis(300));
+
+        // A Retry-After below the base delay is raised to it
+        assert!(limiter.update_delay(url, 429, Some(Duration::from_millis(10))));
+        assert_eq!(limiter.current_delay(url), Some(Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn test_domains_are_independent() {
+        let limiter = RateLimiter::new((10, 10), 1000, 3);
+        limiter.update_delay("https://a.example.com/", 429, None);
+        assert!(limiter.current_delay("https://A.example.com/other").unwrap() > Duration::from_millis(10));
+        assert!(limiter.current_delay("https://b.example.com/").is_none());
+    }
+
+    #[test]
+    fn test_each_request_draws_its_own_delay() {
+        let limiter = RateLimiter::new((0, 1_000_000), 10_000_000, 3);
+        let url = "https://example.com/page";
+        let next_allowed = || limiter.domains.lock().unwrap()[&RateLimiter::domain(url)].next_allowed;
+
+        limiter.reserve_slot(url);
+        let mut gaps = Vec::new();
+        for _ in 0..5 {
+            let before = next_allowed();
+            limiter.reserve_slot(url);
+            gaps.push(next_allowed() - before);
+        }
+        assert!(gaps.iter().any(|gap| *gap != gaps[0]), "Delays should be jittered: {:?}", gaps);
+
+        // While backing off, every slot uses the backoff delay
+        limiter.update_delay(url, 429, Some(Duration::from_secs(5000)));
+        let before = next_allowed();
+        limiter.reserve_slot(url);
+        assert_eq!(next_allowed() - before, Duration::from_secs(5000));
+    }
+
+    #[tokio::test]
+    async fn test_wait_if_needed_spaces_requests() {
+        let limiter = RateLimiter::new((50, 50), 1000, 3);
+        let url = "http://localhost/page";
+
+        let start = Instant::now();
+        limiter.wait_if_needed(url).await;
+        limiter.wait_if_needed(url).await;
+        limiter.wait_if_needed(url).await;
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
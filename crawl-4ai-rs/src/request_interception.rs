@@ -0,0 +1,391 @@
+use anyhow::Result;
+use base64::{engine::general_purpose, Engine as _};
+use chromiumoxide::cdp::browser_protocol::fetch::{self, EventRequestPaused, HeaderEntry, RequestPattern};
+use chromiumoxide::cdp::browser_protocol::network::{self, ErrorReason};
+use chromiumoxide::Page;
+use futures::StreamExt;
+use log::warn;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+use crate::deep_crawl::filters::{compile, glob_to_regex};
+
+/// Kind of resource requested by a page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceType {
+    /// A page or frame.
+    Document,
+    /// A CSS stylesheet.
+    Stylesheet,
+    /// An image.
+    Image,
+    /// Audio or video.
+    Media,
+    /// A web font.
+    Font,
+    /// A script.
+    Script,
+    /// An `XMLHttpRequest`.
+    Xhr,
+    /// A `fetch()` request.
+    Fetch,
+    /// Anything else, e.g. prefetches, pings and web sockets.
+    Other,
+}
+
+impl From<&network::ResourceType> for ResourceType {
+    fn from(resource_type: &network::ResourceType) -> Self {
+        match resource_type {
+            network::ResourceType::Document => Self::Document,
+            network::ResourceType::Stylesheet => Self::Stylesheet,
+            network::ResourceType::Image => Self::Image,
+            network::ResourceType::Media => Self::Media,
+            network::ResourceType::Font => Self::Font,
+            network::ResourceType::Script => Self::Script,
+            network::ResourceType::Xhr => Self::Xhr,
+            network::ResourceType::Fetch => Self::Fetch,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl ResourceType {
+    /// The CDP resource types this type covers.
+    fn to_cdp(self) -> Vec<network::ResourceType> {
+        match self {
+            Self::Document => vec![network::ResourceType::Document],
+            Self::Stylesheet => vec![network::ResourceType::Stylesheet],
+            Self::Image => vec![network::ResourceType::Image],
+            Self::Media => vec![network::ResourceType::Media],
+            Self::Font => vec![network::ResourceType::Font],
+            Self::Script => vec![network::ResourceType::Script],
+            Self::Xhr => vec![network::ResourceType::Xhr],
+            Self::Fetch => vec![network::ResourceType::Fetch],
+            Self::Other => vec![
+                network::ResourceType::TextTrack,
+                network::ResourceType::Prefetch,
+                network::ResourceType::EventSource,
+                network::ResourceType::WebSocket,
+                network::ResourceType::Manifest,
+                network::ResourceType::SignedExchange,
+                network::ResourceType::Ping,
+                network::ResourceType::CspViolationReport,
+                network::ResourceType::Preflight,
+                network::ResourceType::Other,
+            ],
+        }
+    }
+}
+
+/// Requests a browser page should not make.
+///
+/// Blocking the images, fonts, media and stylesheets of text-only crawls saves most of
+/// the page's traffic.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockResources {
+    /// Resource types to block (default: none).
+    pub resource_types: Vec<ResourceType>,
+    /// Glob patterns of URLs to block, where `*` matches any characters, e.g.
+    /// `*://*.doubleclick.net/*` (default: none).
+    pub url_patterns: Vec<String>,
+}
+
+impl BlockResources {
+    /// Blocks images, fonts, media and stylesheets, which text-only crawls do not need.
+    pub fn text_only() -> Self {
+        Self {
+            resource_types: vec![ResourceType::Image, ResourceType::Font, ResourceType::Media, ResourceType::Stylesheet],
+            url_patterns: Vec::new(),
+        }
+    }
+
+    /// Also blocks the URLs matching `patterns`, e.g. ad and analytics domains.
+    pub fn with_url_patterns<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.url_patterns.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns whether nothing is blocked.
+    pub fn is_empty(&self) -> bool {
+        self.resource_types.is_empty() && self.url_patterns.is_empty()
+    }
+
+    fn compile(&self) -> BlockRules {
+        BlockRules {
+            resource_types: self.resource_types.clone(),
+            url_patterns: self.url_patterns.iter().filter_map(|p| compile(&glob_to_regex(p))).collect(),
+        }
+    }
+}
+
+/// [`BlockResources`] with compiled URL patterns.
+struct BlockRules {
+    resource_types: Vec<ResourceType>,
+    url_patterns: Vec<Regex>,
+}
+
+impl BlockRules {
+    fn blocks(&self, url: &str, resource_type: ResourceType) -> bool {
+        self.resource_types.contains(&resource_type) || self.url_patterns.iter().any(|re| re.is_match(url))
+    }
+}
+
+/// A request paused by the browser, as seen by a [`RequestInterceptor`].
+#[derive(Debug, Clone)]
+pub struct InterceptedRequest {
+    /// URL of the request.
+    pub url: String,
+    /// HTTP method, e.g. `GET`.
+    pub method: String,
+    /// Request headers.
+    pub headers: HashMap<String, String>,
+    /// Body of the request, if any.
+    pub post_data: Option<String>,
+    /// Kind of resource requested.
+    pub resource_type: ResourceType,
+}
+
+/// What to do with an intercepted request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InterceptAction {
+    /// Sends the request unchanged.
+    Continue,
+    /// Fails the request as blocked by the client.
+    Abort,
+    /// Sends the request with some of its parts replaced.
+    Modify(RequestOverrides),
+    /// Answers the request with a mock response instead of sending it.
+    Fulfill(MockResponse),
+}
+
+/// Replacements for the parts of an intercepted request.
+///
+/// The browser only lets the URL change to one with the same scheme, and the
+/// change is not visible to the page.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestOverrides {
+    /// URL to request instead (optional).
+    pub url: Option<String>,
+    /// HTTP method to use instead (optional).
+    pub method: Option<String>,
+    /// Headers replacing all of the request's headers (optional).
+    pub headers: Option<HashMap<String, String>>,
+    /// Body to send instead (optional).
+    pub post_data: Option<String>,
+}
+
+/// A response served to the page without making the request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response headers.
+    pub headers: HashMap<String, String>,
+    /// Response body.
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    /// Creates a new `MockResponse` with `status` and `body` and no headers.
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: HashMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Creates a `200 OK` response serving `body` as HTML.
+    pub fn html(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, body).with_header("content-type", "text/html; charset=utf-8")
+    }
+
+    /// Adds the header `name: value`.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+}
+
+/// Decides what happens to each request of the pages crawled on the browser.
+///
+/// Requests blocked by `CrawlerRunConfig::block_resources` are aborted before they reach
+/// the interceptor. Closures taking an [`InterceptedRequest`] implement this trait.
+pub trait RequestInterceptor: Send + Sync {
+    /// Returns the action for `request`.
+    fn intercept(&self, request: &InterceptedRequest) -> InterceptAction;
+}
+
+impl<F> RequestInterceptor for F
+where
+    F: Fn(&InterceptedRequest) -> InterceptAction + Send + Sync,
+{
+    fn intercept(&self, request: &InterceptedRequest) -> InterceptAction {
+        self(request)
+    }
+}
+
+/// Request interception (CDP `Fetch.enable`) running on a page for the duration of a crawl.
+///
+/// Dropping it stops the interception in the background; [`stop`](Self::stop) waits for it.
+pub(crate) struct PageInterception {
+    page: Page,
+    task: Option<JoinHandle<()>>,
+}
+
+impl PageInterception {
+    /// Starts applying `block` and `interceptor` to the requests of `page`. Returns `None`
+    /// if there is nothing to intercept.
+    pub(crate) async fn start(
+        page: &Page,
+        block: Option<&BlockResources>,
+        interceptor: Option<Arc<dyn RequestInterceptor>>,
+    ) -> Result<Option<Self>> {
+        let block = block.filter(|b| !b.is_empty());
+        if block.is_none() && interceptor.is_none() {
+            return Ok(None);
+        }
+
+        // Without URL patterns or an interceptor only the blocked types need to pause
+        let patterns = match block {
+            Some(block) if interceptor.is_none() && block.url_patterns.is_empty() => block
+                .resource_types
+                .iter()
+                .flat_map(|t| t.to_cdp())
+                .map(|t| RequestPattern::builder().url_pattern("*").resource_type(t).build())
+                .collect(),
+            _ => vec![RequestPattern::builder().url_pattern("*").build()],
+        };
+        let rules = block.map(|b| b.compile());
+
+        let mut paused = page.event_listener::<EventRequestPaused>().await?;
+        page.execute(fetch::EnableParams::builder().patterns(patterns).build()).await?;
+
+        let task_page = page.clone();
+        let task = tokio::spawn(async move {
+            while let Some(event) = paused.next().await {
+                let action = Self::action(&event, rules.as_ref(), interceptor.as_deref());
+                let page = task_page.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = Self::apply(&page, &event, action).await {
+                        warn!("Failed to handle intercepted request {}: {}", event.request.url, e);
+                    }
+                });
+            }
+        });
+
+        Ok(Some(Self {
+            page: page.clone(),
+            task: Some(task),
+        }))
+    }
+
+    /// Stops intercepting the page's requests.
+    pub(crate) async fn stop(mut self) {
+        if let Some(task) = self.task.take() {
+            // Disable first so that no request stays paused without a handler
+            let _ = self.page.execute(fetch::DisableParams::default()).await;
+            task.abort();
+        }
+    }
+
+    fn action(
+        event: &EventRequestPaused,
+        rules: Option<&BlockRules>,
+        interceptor: Option<&dyn RequestInterceptor>,
+    ) -> InterceptAction {
+        let resource_type = ResourceType::from(&event.resource_type);
+        if rules.map(|r| r.blocks(&event.request.url, resource_type)).unwrap_or(false) {
+            return InterceptAction::Abort;
+        }
+        let Some(interceptor) = interceptor else { return InterceptAction::Continue };
+
+        let headers = match event.request.headers.inner() {
+            serde_json::Value::Object(map) => map
+                .iter()
+                .map(|(name, value)| (name.clone(), value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string())))
+                .collect(),
+            _ => HashMap::new(),
+        };
+        interceptor.intercept(&InterceptedRequest {
+            url: event.request.url.clone(),
+            method: event.request.method.clone(),
+            headers,
+            post_data: event.request.post_data.clone(),
+            resource_type,
+        })
+    }
+
+    async fn apply(page: &Page, event: &EventRequestPaused, action: InterceptAction) -> Result<()> {
+        let request_id = event.request_id.clone();
+        match action {
+            InterceptAction::Continue => {
+                page.execute(fetch::ContinueRequestParams::new(request_id)).await?;
+            }
+            InterceptAction::Abort => {
+                page.execute(fetch::FailRequestParams::new(request_id, ErrorReason::BlockedByClient)).await?;
+            }
+            InterceptAction::Modify(overrides) => {
+                let mut params = fetch::ContinueRequestParams::new(request_id);
+                params.url = overrides.url;
+                params.method = overrides.method;
+                params.headers = overrides.headers.map(header_entries);
+                params.post_data = overrides.post_data.map(|body| general_purpose::STANDARD.encode(body).into());
+                page.execute(params).await?;
+            }
+            InterceptAction::Fulfill(response) => {
+                let mut params = fetch::FulfillRequestParams::new(request_id, response.status as i64);
+                params.response_headers = Some(header_entries(response.headers));
+                params.body = Some(general_purpose::STANDARD.encode(response.body).into());
+                page.execute(params).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PageInterception {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            let page = self.page.clone();
+            tokio::spawn(async move {
+                let _ = page.execute(fetch::DisableParams::default()).await;
+                task.abort();
+            });
+        }
+    }
+}
+
+fn header_entries(headers: HashMap<String, String>) -> Vec<HeaderEntry> {
+    headers.into_iter().map(|(name, value)| HeaderEntry::new(name, value)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_rules() {
+        let block = BlockResources::text_only().with_url_patterns(["*://*.doubleclick.net/*"]);
+        let rules = block.compile();
+
+        assert!(rules.blocks("https://example.com/logo.png", ResourceType::Image));
+        assert!(rules.blocks("https://ad.doubleclick.net/pixel.js", ResourceType::Script));
+        assert!(!rules.blocks("https://example.com/app.js", ResourceType::Script));
+        assert!(!rules.blocks("https://example.com/", ResourceType::Document));
+
+        assert!(BlockResources::default().is_empty());
+        let parsed: BlockResources = serde_json::from_str(r#"{"resource_types": ["image", "xhr"]}"#).unwrap();
+        assert_eq!(parsed.resource_types, vec![ResourceType::Image, ResourceType::Xhr]);
+        assert_eq!(ResourceType::from(&network::ResourceType::Ping), ResourceType::Other);
+    }
+}
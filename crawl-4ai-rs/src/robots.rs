@@ -0,0 +1,388 @@
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use url::Url;
+
+/// User agent token matched against `robots.txt` groups when the browser has no custom user agent.
+pub const DEFAULT_ROBOTS_USER_AGENT: &str = "crawl4ai";
+
+/// How long an unreachable `robots.txt` (server error or network failure) blocks its
+/// origin before it is fetched again.
+const UNREACHABLE_TTL: Duration = Duration::from_secs(60);
+
+/// A single `Allow` or `Disallow` rule.
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: String,
+    allow: bool,
+}
+
+/// Rules that apply to one or more user agents.
+#[derive(Debug, Clone, Default)]
+struct Group {
+    user_agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+/// A parsed `robots.txt` file.
+#[derive(Debug, Clone, Default)]
+pub struct RobotsTxt {
+    groups: Vec<Group>,
+    /// Sitemap URLs listed in the file.
+    pub sitemaps: Vec<String>,
+}
+
+impl RobotsTxt {
+    /// Parses the content of a `robots.txt` file.
+    pub fn parse(content: &str) -> Self {
+        let mut robots = RobotsTxt::default();
+        let mut current: Option<Group> = None;
+        // Consecutive `User-agent` lines share a group; any other directive closes the list.
+        let mut collecting_agents = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if !collecting_agents {
+                        if let Some(group) = current.take() {
+                            robots.groups.push(group);
+                        }
+                        current = Some(Group::default());
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.user_agents.push(value.to_lowercase());
+                    }
+                    collecting_agents = true;
+                }
+                "allow" | "disallow" => {
+                    collecting_agents = false;
+                    if let Some(group) = current.as_mut() {
+                        // An empty `Disallow:` allows everything and adds no rule.
+                        if !value.is_empty() {
+                            group.rules.push(Rule {
+                                pattern: value.to_string(),
+                                allow: key == "allow",
+                            });
+                        }
+                    }
+                }
+                "crawl-delay" => {
+                    collecting_agents = false;
+                    if let Some(group) = current.as_mut() {
+                        if let Ok(secs) = value.parse::<f64>() {
+                            if secs.is_finite() && secs >= 0.0 {
+                                group.crawl_delay = Some(Duration::from_secs_f64(secs));
+                            }
+                        }
+                    }
+                }
+                "sitemap" if !value.is_empty() => {
+                    robots.sitemaps.push(value.to_string());
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(group) = current {
+            robots.groups.push(group);
+        }
+
+        robots
+    }
+
+    /// Returns a `robots.txt` that disallows everything for every user agent.
+    pub fn disallow_all() -> Self {
+        Self::parse("User-agent: *\nDisallow: /\n")
+    }
+
+    /// Returns whether `user_agent` may fetch `url`.
+    pub fn can_fetch(&self, url: &str, user_agent: &str) -> bool {
+        let path = match Url::parse(url) {
+            Ok(u) => match u.query() {
+                Some(q) => format!("{}?{}", u.path(), q),
+                None => u.path().to_string(),
+            },
+            Err(_) => return true,
+        };
+
+        // The longest matching pattern wins; on a tie, `Allow` wins.
+        let mut best: Option<(usize, bool)> = None;
+        for group in self.groups_for(user_agent) {
+            for rule in &group.rules {
+                if pattern_matches(&rule.pattern, &path) {
+                    let len = rule.pattern.len();
+                    best = match best {
+                        Some((best_len, best_allow)) if best_len > len || (best_len == len && best_allow) => {
+                            Some((best_len, best_allow))
+                        }
+                        _ => Some((len, rule.allow)),
+                    };
+                }
+            }
+        }
+
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+
+    /// Returns the `Crawl-delay` that applies to `user_agent`, if any.
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<Duration> {
+        self.groups_for(user_agent).into_iter().find_map(|g| g.crawl_delay)
+    }
+
+    /// Selects the groups whose user agent token matches `user_agent` most specifically,
+    /// falling back to the `*` groups.
+    fn groups_for(&self, user_agent: &str) -> Vec<&Group> {
+        let user_agent = user_agent.to_lowercase();
+
+        let best_token = self
+            .groups
+            .iter()
+            .flat_map(|g| g.user_agents.iter())
+            .filter(|token| token.as_str() != "*" && user_agent.contains(token.as_str()))
+            .max_by_key(|token| token.len());
+
+        let token = best_token.map(|t| t.as_str()).unwrap_or("*");
+        self.groups
+            .iter()
+            .filter(|g| g.user_agents.iter().any(|a| a == token))
+            .collect()
+    }
+}
+
+/// Matches a robots.txt path pattern supporting `*` wildcards and a trailing `$` anchor.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut pos = 0;
+
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            if !path.starts_with(part) {
+                return false;
+            }
+            pos = part.len();
+        } else if i == parts.len() - 1 && anchored {
+            // The last segment must sit at the very end of the path.
+            return path.len() >= pos + part.len() && path.ends_with(part);
+        } else {
+            match path[pos..].find(part) {
+                Some(idx) => pos += idx + part.len(),
+                None => return false,
+            }
+        }
+    }
+
+    !anchored || pos == path.len()
+}
+
+#[derive(Debug)]
+struct CachedRobots {
+    robots: Arc<RobotsTxt>,
+    expires_at: Instant,
+    next_allowed: Instant,
+}
+
+/// Cache slot of one origin, locked while its file is fetched.
+type CacheEntry = Arc<Mutex<Option<CachedRobots>>>;
+
+/// Fetches `robots.txt` files and caches them per origin.
+#[derive(Debug)]
+pub struct RobotsChecker {
+    client: Client,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl Default for RobotsChecker {
+    fn default() -> Self {
+        // Cache entries for a day.
+        Self::new(Duration::from_secs(24 * 60 * 60))
+    }
+}
+
+impl RobotsChecker {
+    /// Creates a new `RobotsChecker` whose cached files expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns whether `user_agent` may fetch `url`. URLs that are not HTTP(S) are always allowed.
+    pub async fn can_fetch(&self, url: &str, user_agent: &str) -> bool {
+        match self.robots_for(url).await {
+            Some(robots) => robots.can_fetch(url, user_agent),
+            None => true,
+        }
+    }
+
+    /// Waits for the `Crawl-delay` of the origin of `url` to elapse since the previous request.
+    pub async fn wait_for_crawl_delay(&self, url: &str, user_agent: &str) {
+        let Some(origin) = Self::origin(url) else { return };
+
+        let Some(entry) = self.cache.lock().await.get(&origin).cloned() else { return };
+        let wait = {
+            let mut entry = entry.lock().await;
+            let Some(entry) = entry.as_mut() else { return };
+            let Some(delay) = entry.robots.crawl_delay(user_agent) else { return };
+
+            let now = Instant::now();
+            let start = entry.next_allowed.max(now);
+            entry.next_allowed = start + delay;
+            start - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Returns the parsed `robots.txt` for the origin of `url`, fetching it if it is not cached.
+    pub async fn robots_for(&self, url: &str) -> Option<Arc<RobotsTxt>> {
+        let origin = Self::origin(url)?;
+
+        let entry = self.cache.lock().await.entry(origin.clone()).or_default().clone();
+
+        // Hold the origin's lock while fetching so its file is only downloaded once,
+        // without blocking checks for other origins.
+        let mut entry = entry.lock().await;
+        if let Some(cached) = entry.as_ref() {
+            if Instant::now() < cached.expires_at {
+                return Some(cached.robots.clone());
+            }
+        }
+
+        let (robots, reachable) = self.fetch(&origin).await;
+        let robots = Arc::new(robots);
+        let now = Instant::now();
+        let ttl = if reachable { self.ttl } else { self.ttl.min(UNREACHABLE_TTL) };
+        let next_allowed = entry.as_ref().map(|c| c.next_allowed).unwrap_or(now);
+        *entry = Some(CachedRobots {
+            robots: robots.clone(),
+            expires_at: now + ttl,
+            next_allowed,
+        });
+        Some(robots)
+    }
+
+    /// Downloads and parses `robots.txt`, returning it and whether it was reachable.
+    ///
+    /// As in RFC 9309, a missing file (4xx) allows everything, while a server error or
+    /// a network failure disallows everything.
+    async fn fetch(&self, origin: &str) -> (RobotsTxt, bool) {
+        let robots_url = format!("{}/robots.txt", origin);
+        let resp = match self.client.get(&robots_url).send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                eprintln!("Failed to fetch {}: {}", robots_url, e);
+                return (RobotsTxt::disallow_all(), false);
+            }
+        };
+
+        let status = resp.status();
+        if status.is_client_error() {
+            return (RobotsTxt::default(), true);
+        }
+        if !status.is_success() {
+            eprintln!("{} returned status {}", robots_url, status);
+            return (RobotsTxt::disallow_all(), false);
+        }
+        match resp.text().await {
+            Ok(body) => (RobotsTxt::parse(&body), true),
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", robots_url, e);
+                (RobotsTxt::disallow_all(), false)
+            }
+        }
+    }
+
+    fn origin(url: &str) -> Option<String> {
+        let parsed = Url::parse(url).ok()?;
+        match parsed.scheme() {
+            "http" | "https" => Some(parsed.origin().ascii_serialization()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = r#"
+# Example robots.txt
+User-agent: *
+Disallow: /private/
+Allow: /private/public-page
+Disallow: /*.pdf$
+Crawl-delay: 2
+
+User-agent: crawl4ai
+User-agent: OtherBot
+Disallow: /no-crawl4ai/
+Crawl-delay: 0.5
+
+Sitemap: https://example.com/sitemap.xml
+"#;
+
+    #[test]
+    fn test_wildcard_group() {
+        let robots = RobotsTxt::parse(ROBOTS);
+        let ua = "SomeBot/1.0";
+
+        assert!(robots.can_fetch("https://example.com/", ua));
+        assert!(!robots.can_fetch("https://example.com/private/secret", ua));
+        assert!(robots.can_fetch("https://example.com/private/public-page", ua));
+        assert!(!robots.can_fetch("https://example.com/docs/file.pdf", ua));
+        assert!(robots.can_fetch("https://example.com/docs/file.pdf?download=1", ua));
+        assert_eq!(robots.crawl_delay(ua), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_user_agent_specific_group() {
+        let robots = RobotsTxt::parse(ROBOTS);
+        let ua = "Mozilla/5.0 (compatible; Crawl4AI/0.1)";
+
+        // The specific group replaces the `*` group entirely.
+        assert!(robots.can_fetch("https://example.com/private/secret", ua));
+        assert!(!robots.can_fetch("https://example.com/no-crawl4ai/page", ua));
+        assert!(!robots.can_fetch("https://example.com/no-crawl4ai/page", "otherbot"));
+        assert_eq!(robots.crawl_delay(ua), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_sitemaps_and_empty_disallow() {
+        let robots = RobotsTxt::parse("User-agent: *\nDisallow:\nSitemap: https://example.com/a.xml\n");
+        assert!(robots.can_fetch("https://example.com/anything", "bot"));
+        assert_eq!(robots.sitemaps, vec!["https://example.com/a.xml".to_string()]);
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/", "/index.html"));
+        assert!(pattern_matches("/*/edit", "/posts/1/edit"));
+        assert!(pattern_matches("/page$", "/page"));
+        assert!(!pattern_matches("/page$", "/page2"));
+        assert!(pattern_matches("/*.php$", "/a/b.php"));
+        assert!(!pattern_matches("/*.php$", "/a/b.php5"));
+        assert!(!pattern_matches("/admin", "/public/admin"));
+    }
+}
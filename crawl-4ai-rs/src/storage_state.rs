@@ -0,0 +1,248 @@
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::network::{self, CookieParam, CookieSameSite, TimeSinceEpoch};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// `sessionStorage` key marking an origin whose storage was already seeded in a tab.
+const SEEDED_MARKER: &str = "__crawl4ai_storage_state";
+
+/// Browser state in Playwright's `storageState` JSON format: cookies plus the
+/// `localStorage` (and `sessionStorage`) of each origin.
+///
+/// Load one saved by Playwright or by [`AsyncWebCrawler::export_storage_state`] and set it
+/// as `BrowserConfig::storage_state` to start crawls logged in.
+///
+/// [`AsyncWebCrawler::export_storage_state`]: crate::crawler::AsyncWebCrawler::export_storage_state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StorageState {
+    /// Cookies of the browser context.
+    #[serde(default)]
+    pub cookies: Vec<Cookie>,
+    /// Web storage by origin.
+    #[serde(default)]
+    pub origins: Vec<OriginState>,
+}
+
+/// A cookie in Playwright's format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    /// Cookie name.
+    pub name: String,
+    /// Cookie value.
+    pub value: String,
+    /// URL the cookie is set for, as an alternative to `domain` and `path` (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Domain of the cookie; a leading dot includes subdomains (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// Path of the cookie (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Expiry as Unix time in seconds; -1 for a session cookie (default: -1).
+    #[serde(default = "session_expiry")]
+    pub expires: f64,
+    /// Whether the cookie is hidden from JavaScript (default: false).
+    #[serde(default)]
+    pub http_only: bool,
+    /// Whether the cookie is only sent over HTTPS (default: false).
+    #[serde(default)]
+    pub secure: bool,
+    /// `SameSite` attribute of the cookie (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<SameSite>,
+}
+
+/// `SameSite` attribute of a cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SameSite {
+    /// Only sent with requests from the cookie's own site.
+    Strict,
+    /// Also sent with top-level navigations from other sites.
+    Lax,
+    /// Sent with all requests; requires `secure`.
+    None,
+}
+
+/// Web storage of one origin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginState {
+    /// Origin such as `https://example.com`.
+    pub origin: String,
+    /// `localStorage` entries.
+    #[serde(default)]
+    pub local_storage: Vec<StorageItem>,
+    /// `sessionStorage` entries. Not part of Playwright's format, which ignores them (default: none).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub session_storage: Vec<StorageItem>,
+}
+
+/// A web storage entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageItem {
+    /// Storage key.
+    pub name: String,
+    /// Stored value.
+    pub value: String,
+}
+
+fn session_expiry() -> f64 {
+    -1.0
+}
+
+impl StorageState {
+    /// Reads a storage state JSON file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read(path).with_context(|| format!("Failed to read storage state {}", path.display()))?;
+        serde_json::from_slice(&content).with_context(|| format!("Failed to parse storage state {}", path.display()))
+    }
+
+    /// Writes the storage state to `path` as JSON.
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(path, serde_json::to_vec_pretty(self)?).await?;
+        Ok(())
+    }
+
+    /// Returns a script that seeds the web storage of the page's origin on every new
+    /// document, or `None` if there is no web storage to seed.
+    ///
+    /// Each origin is seeded once per tab, so later changes made by the site are kept.
+    pub(crate) fn init_script(&self) -> Option<String> {
+        if self.origins.is_empty() {
+            return None;
+        }
+        let origins = serde_json::to_string(&self.origins).ok()?;
+        Some(format!(
+            r#"(() => {{
+                const state = {origins}.find(o => o.origin === location.origin);
+                if (!state) return;
+                try {{
+                    if (sessionStorage.getItem("{marker}")) return;
+                    for (const {{ name, value }} of state.localStorage || []) localStorage.setItem(name, value);
+                    for (const {{ name, value }} of state.sessionStorage || []) sessionStorage.setItem(name, value);
+                    sessionStorage.setItem("{marker}", "1");
+                }} catch (e) {{}}
+            }})();"#,
+            origins = origins,
+            marker = SEEDED_MARKER,
+        ))
+    }
+
+    /// Replaces the entry for `state.origin`, or adds it.
+    pub(crate) fn set_origin(&mut self, state: OriginState) {
+        match self.origins.iter_mut().find(|o| o.origin == state.origin) {
+            Some(existing) => *existing = state,
+            None => self.origins.push(state),
+        }
+    }
+}
+
+/// Script returning the web storage of the page's origin as an [`OriginState`].
+pub(crate) fn export_script() -> String {
+    format!(
+        r#"(() => {{
+            const items = (storage) => Object.keys(storage)
+                .filter(name => name !== "{marker}")
+                .map(name => ({{ name, value: storage.getItem(name) }}));
+            return {{ origin: location.origin, localStorage: items(localStorage), sessionStorage: items(sessionStorage) }};
+        }})()"#,
+        marker = SEEDED_MARKER,
+    )
+}
+
+impl Cookie {
+    /// Converts the cookie to the parameters of `Storage.setCookies`.
+    pub(crate) fn to_cdp(&self) -> CookieParam {
+        let mut param = CookieParam::new(self.name.clone(), self.value.clone());
+        param.url = self.url.clone();
+        param.domain = self.domain.clone();
+        param.path = self.path.clone();
+        param.secure = Some(self.secure);
+        param.http_only = Some(self.http_only);
+        param.same_site = self.same_site.map(|s| match s {
+            SameSite::Strict => CookieSameSite::Strict,
+            SameSite::Lax => CookieSameSite::Lax,
+            SameSite::None => CookieSameSite::None,
+        });
+        if self.expires >= 0.0 {
+            param.expires = Some(TimeSinceEpoch::new(self.expires));
+        }
+        param
+    }
+
+    /// Converts a cookie returned by `Storage.getCookies`.
+    pub(crate) fn from_cdp(cookie: &network::Cookie) -> Self {
+        Self {
+            name: cookie.name.clone(),
+            value: cookie.value.clone(),
+            url: None,
+            domain: Some(cookie.domain.clone()),
+            path: Some(cookie.path.clone()),
+            expires: if cookie.session { -1.0 } else { cookie.expires },
+            http_only: cookie.http_only,
+            secure: cookie.secure,
+            same_site: cookie.same_site.as_ref().map(|s| match s {
+                CookieSameSite::Strict => SameSite::Strict,
+                CookieSameSite::Lax => SameSite::Lax,
+                CookieSameSite::None => SameSite::None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_playwright_state() {
+        let json = r#"{
+            "cookies": [
+                {"name": "sid", "value": "abc", "domain": ".example.com", "path": "/", "expires": -1,
+                 "httpOnly": true, "secure": true, "sameSite": "Lax"},
+                {"name": "theme", "value": "dark", "url": "https://example.com"}
+            ],
+            "origins": [
+                {"origin": "https://example.com", "localStorage": [{"name": "token", "value": "t1"}]}
+            ]
+        }"#;
+
+        let state: StorageState = serde_json::from_str(json).unwrap();
+        assert_eq!(state.cookies[0].same_site, Some(SameSite::Lax));
+        assert_eq!(state.cookies[1].expires, -1.0);
+        assert!(state.origins[0].session_storage.is_empty());
+
+        let param = state.cookies[0].to_cdp();
+        assert_eq!(param.domain.as_deref(), Some(".example.com"));
+        assert_eq!(param.http_only, Some(true));
+        assert!(param.expires.is_none());
+
+        // Round-trips without adding fields Playwright does not know
+        let value = serde_json::to_value(&state).unwrap();
+        assert!(value["origins"][0].get("sessionStorage").is_none());
+        assert_eq!(serde_json::from_value::<StorageState>(value).unwrap(), state);
+    }
+
+    #[test]
+    fn test_set_origin_and_init_script() {
+        let mut state = StorageState::default();
+        assert!(state.init_script().is_none());
+
+        let origin = |value: &str| OriginState {
+            origin: "https://example.com".to_string(),
+            local_storage: vec![StorageItem { name: "token".to_string(), value: value.to_string() }],
+            session_storage: Vec::new(),
+        };
+        state.set_origin(origin("old"));
+        state.set_origin(origin("new"));
+
+        assert_eq!(state.origins.len(), 1);
+        assert_eq!(state.origins[0].local_storage[0].value, "new");
+        assert!(state.init_script().unwrap().contains(r#""value":"new""#));
+    }
+}
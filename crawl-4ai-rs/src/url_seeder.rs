@@ -0,0 +1,498 @@
+use crate::content_filter::bm25::{bm25_scores, tokenize};
+use crate::crawler_strategy::{read_body, read_head, site_client};
+use crate::deep_crawl::filters::UrlPatternFilter;
+use crate::models::BrowserConfig;
+use crate::robots::RobotsChecker;
+use anyhow::{anyhow, Result};
+use flate2::read::GzDecoder;
+use futures::stream::{self, StreamExt};
+use kuchiki::traits::*;
+use reqwest::Client;
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Read;
+use std::time::Duration;
+use url::Url;
+
+/// Largest sitemap or feed read, before and after decompression (50 MB, the sitemap limit).
+const MAX_DOCUMENT_BYTES: usize = 50 * 1024 * 1024;
+
+/// Largest part of a page read to find its `<head>` (256 KB).
+const MAX_HEAD_BYTES: usize = 256 * 1024;
+
+/// Configuration for [`UrlSeeder::urls`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeedingConfig {
+    /// Whether to read the sitemaps listed in `robots.txt`, or `/sitemap.xml` if none are (default: true).
+    pub use_sitemaps: bool,
+    /// Whether to read the RSS and Atom feeds linked from the home page (default: true).
+    pub use_feeds: bool,
+    /// Glob patterns that URLs must match, e.g. `*/blog/*` (default: all URLs).
+    pub patterns: Vec<String>,
+    /// Whether to fetch each URL and parse the metadata in its `<head>` (default: false).
+    pub extract_head: bool,
+    /// Query to rank URLs by BM25 relevance of their URL and head metadata (default: none).
+    pub query: Option<String>,
+    /// Minimum relevance score for a URL to be kept when `query` is set (default: none).
+    pub score_threshold: Option<f32>,
+    /// Maximum number of URLs returned (default: unlimited).
+    pub max_urls: Option<usize>,
+    /// Maximum number of sitemap and feed documents fetched, including nested ones (default: 100).
+    pub max_sitemaps: usize,
+    /// Number of `<head>` requests sent concurrently (default: 10).
+    pub concurrency: usize,
+    /// Timeout of each request in milliseconds (default: 10000ms).
+    pub timeout_ms: u64,
+}
+
+impl Default for SeedingConfig {
+    fn default() -> Self {
+        Self {
+            use_sitemaps: true,
+            use_feeds: true,
+            patterns: Vec::new(),
+            extract_head: false,
+            query: None,
+            score_threshold: None,
+            max_urls: None,
+            max_sitemaps: 100,
+            concurrency: 10,
+            timeout_ms: 10_000,
+        }
+    }
+}
+
+/// Metadata read from the `<head>` of a page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeadData {
+    /// Content of the `<title>` element.
+    pub title: Option<String>,
+    /// `<meta>` contents keyed by their lowercased `name` or `property`.
+    pub meta: HashMap<String, String>,
+}
+
+/// A URL discovered by [`UrlSeeder`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeededUrl {
+    /// Absolute URL of the page.
+    pub url: String,
+    /// Last modification date given by the sitemap or feed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lastmod: Option<String>,
+    /// Head metadata, if `extract_head` was set and the page could be fetched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<HeadData>,
+    /// BM25 relevance to the query, if one was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relevance_score: Option<f32>,
+}
+
+/// Discovers the URLs of a site from its sitemaps and feeds, without crawling it.
+pub struct UrlSeeder {
+    client: Client,
+}
+
+impl Default for UrlSeeder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UrlSeeder {
+    /// Creates a new `UrlSeeder` sending the crawler's default user agent.
+    pub fn new() -> Self {
+        Self::with_config(&BrowserConfig::default())
+    }
+
+    /// Creates a new `UrlSeeder` that fetches with the user agent, locale and proxy of
+    /// `browser_config`, like a crawler launched with it.
+    pub fn with_config(browser_config: &BrowserConfig) -> Self {
+        let client = site_client(browser_config, browser_config.proxy.as_ref()).unwrap_or_else(|e| {
+            eprintln!("Failed to build HTTP client, using defaults: {}", e);
+            Client::new()
+        });
+        Self { client }
+    }
+
+    /// Returns the URLs of `domain`, ranked by relevance if `config.query` is set.
+    ///
+    /// `domain` may be a bare domain such as `example.com`, which is fetched over HTTPS, or
+    /// an origin such as `http://localhost:8080`. Sitemap indexes are followed, gzipped
+    /// sitemaps are decompressed and unreachable documents are skipped.
+    pub async fn urls(&self, domain: &str, config: &SeedingConfig) -> Result<Vec<SeededUrl>> {
+        let origin = origin_of(domain)?;
+        let timeout = Duration::from_millis(config.timeout_ms);
+
+        let mut sources = Vec::new();
+        if config.use_sitemaps {
+            let robots = RobotsChecker::default().robots_for(&origin).await;
+            match robots {
+                Some(robots) if !robots.sitemaps.is_empty() => sources.extend(robots.sitemaps.iter().cloned()),
+                _ => sources.push(format!("{}/sitemap.xml", origin)),
+            }
+        }
+        if config.use_feeds {
+            sources.extend(self.feed_links(&origin, timeout).await);
+        }
+
+        let filter = (!config.patterns.is_empty()).then(|| UrlPatternFilter::new(config.patterns.clone()));
+        let mut seen = HashSet::new();
+        let mut urls: Vec<SeededUrl> = self
+            .read_sources(sources, config.max_sitemaps, timeout)
+            .await
+            .into_iter()
+            .filter(|entry| seen.insert(entry.url.clone()))
+            .filter(|entry| filter.as_ref().map(|f| f.accepts(&entry.url)).unwrap_or(true))
+            .collect();
+
+        if config.extract_head {
+            let heads: Vec<Option<HeadData>> = stream::iter(urls.iter().map(|entry| self.fetch_head(&entry.url, timeout)))
+                .buffered(config.concurrency.max(1))
+                .collect()
+                .await;
+            for (entry, head) in urls.iter_mut().zip(heads) {
+                entry.head = head;
+            }
+        }
+
+        if let Some(ref query) = config.query {
+            score(&mut urls, query);
+            if let Some(threshold) = config.score_threshold {
+                urls.retain(|entry| entry.relevance_score.unwrap_or(0.0) >= threshold);
+            }
+            urls.sort_by(|a, b| b.relevance_score.unwrap_or(0.0).total_cmp(&a.relevance_score.unwrap_or(0.0)));
+        }
+        if let Some(max_urls) = config.max_urls {
+            urls.truncate(max_urls);
+        }
+
+        Ok(urls)
+    }
+
+    /// Reads sitemaps and feeds breadth-first, following sitemap indexes.
+    async fn read_sources(&self, sources: Vec<String>, max_documents: usize, timeout: Duration) -> Vec<SeededUrl> {
+        let mut queue: VecDeque<String> = sources.into();
+        let mut visited = HashSet::new();
+        let mut urls = Vec::new();
+
+        while let Some(source) = queue.pop_front() {
+            if visited.len() >= max_documents {
+                eprintln!("Stopped reading sitemaps after {} documents", max_documents);
+                break;
+            }
+            if !visited.insert(source.clone()) {
+                continue;
+            }
+
+            let Some(body) = self.fetch_document(&source, timeout).await else { continue };
+            match parse_document(&body, &source) {
+                Ok(Document::Index(sitemaps)) => queue.extend(sitemaps),
+                Ok(Document::Urls(entries)) => urls.extend(entries),
+                Err(e) => eprintln!("Failed to parse {}: {}", source, e),
+            }
+        }
+
+        urls
+    }
+
+    /// Downloads a sitemap or feed, decompressing it if it is gzipped.
+    async fn fetch_document(&self, url: &str, timeout: Duration) -> Option<String> {
+        let response = match self.client.get(url).timeout(timeout).send().await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                eprintln!("Failed to fetch {}: HTTP {}", url, response.status());
+                return None;
+            }
+            Err(e) => {
+                eprintln!("Failed to fetch {}: {}", url, e);
+                return None;
+            }
+        };
+        let (bytes, truncated) = read_body(response, MAX_DOCUMENT_BYTES, |_| false).await.ok()?;
+        if truncated {
+            eprintln!("Skipping {}: larger than {} bytes", url, MAX_DOCUMENT_BYTES);
+            return None;
+        }
+
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            return match gunzip(&bytes, MAX_DOCUMENT_BYTES) {
+                Ok(text) => Some(String::from_utf8_lossy(&text).into_owned()),
+                Err(e) => {
+                    eprintln!("Failed to decompress {}: {}", url, e);
+                    None
+                }
+            };
+        }
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Returns the RSS and Atom feeds linked from the home page.
+    async fn feed_links(&self, origin: &str, timeout: Duration) -> Vec<String> {
+        let home = format!("{}/", origin);
+        let Some(html) = self.fetch_head_html(&home, timeout).await else { return Vec::new() };
+
+        let document = kuchiki::parse_html().one(html);
+        let Ok(links) = document.select("link[rel=alternate][href]") else { return Vec::new() };
+        links
+            .filter_map(|link| {
+                let attrs = link.attributes.borrow();
+                let kind = attrs.get("type")?.to_lowercase();
+                if kind != "application/rss+xml" && kind != "application/atom+xml" {
+                    return None;
+                }
+                Url::parse(&home).ok()?.join(attrs.get("href")?).ok().map(String::from)
+            })
+            .collect()
+    }
+
+    /// Fetches the start of `url`, up to the end of its `<head>`, and parses its metadata.
+    async fn fetch_head(&self, url: &str, timeout: Duration) -> Option<HeadData> {
+        Some(parse_head(&self.fetch_head_html(url, timeout).await?))
+    }
+
+    /// Fetches the start of `url`, up to the end of its `<head>`.
+    async fn fetch_head_html(&self, url: &str, timeout: Duration) -> Option<String> {
+        let response = self.client.get(url).timeout(timeout).send().await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let html = read_head(response, MAX_HEAD_BYTES).await.ok()?;
+        Some(String::from_utf8_lossy(&html).into_owned())
+    }
+}
+
+/// Decompresses gzipped `bytes`, failing if the result would exceed `limit` bytes.
+fn gunzip(bytes: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(bytes).take(limit as u64 + 1).read_to_end(&mut decompressed)?;
+    if decompressed.len() > limit {
+        return Err(anyhow!("decompressed size exceeds {} bytes", limit));
+    }
+    Ok(decompressed)
+}
+
+fn origin_of(domain: &str) -> Result<String> {
+    let domain = domain.trim().trim_end_matches('/');
+    let url = if domain.contains("://") {
+        Url::parse(domain)?
+    } else {
+        Url::parse(&format!("https://{}", domain))?
+    };
+    match url.scheme() {
+        "http" | "https" => Ok(url.origin().ascii_serialization()),
+        scheme => Err(anyhow!("Unsupported scheme for URL seeding: {}", scheme)),
+    }
+}
+
+/// Contents of a sitemap or feed.
+#[derive(Debug, PartialEq)]
+enum Document {
+    /// A sitemap index listing further sitemaps.
+    Index(Vec<String>),
+    /// A sitemap or feed listing pages.
+    Urls(Vec<SeededUrl>),
+}
+
+/// Parses a sitemap, sitemap index, RSS feed or Atom feed fetched from `source`.
+fn parse_document(xml: &str, source: &str) -> Result<Document> {
+    let document = roxmltree::Document::parse(xml.trim_start_matches('\u{feff}').trim_start())?;
+    let root = document.root_element();
+    let child_text = |node: roxmltree::Node, name: &str| -> Option<String> {
+        node.children()
+            .find(|c| c.tag_name().name() == name)
+            .and_then(|c| c.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+    let entry = |url: String, lastmod: Option<String>| {
+        // Relative links are allowed in feeds
+        let url = Url::parse(source).and_then(|base| base.join(&url)).map(String::from).unwrap_or(url);
+        SeededUrl {
+            url,
+            lastmod,
+            head: None,
+            relevance_score: None,
+        }
+    };
+    let elements = |name: &'static str| root.descendants().filter(move |n| n.tag_name().name() == name);
+
+    let parsed = match root.tag_name().name() {
+        "sitemapindex" => Document::Index(elements("sitemap").filter_map(|n| child_text(n, "loc")).collect()),
+        "urlset" => Document::Urls(
+            elements("url")
+                .filter_map(|n| Some(entry(child_text(n, "loc")?, child_text(n, "lastmod"))))
+                .collect(),
+        ),
+        "rss" | "RDF" => Document::Urls(
+            elements("item")
+                .filter_map(|n| {
+                    let date = child_text(n, "pubDate").or_else(|| child_text(n, "date"));
+                    Some(entry(child_text(n, "link")?, date))
+                })
+                .collect(),
+        ),
+        "feed" => Document::Urls(
+            elements("entry")
+                .filter_map(|n| {
+                    let href = n
+                        .children()
+                        .filter(|c| c.tag_name().name() == "link")
+                        .find(|c| matches!(c.attribute("rel"), None | Some("alternate")))?
+                        .attribute("href")?;
+                    let updated = child_text(n, "updated").or_else(|| child_text(n, "published"));
+                    Some(entry(href.to_string(), updated))
+                })
+                .collect(),
+        ),
+        other => return Err(anyhow!("Unknown document type <{}>", other)),
+    };
+    Ok(parsed)
+}
+
+fn parse_head(html: &str) -> HeadData {
+    // Only the head is needed, so skip parsing the body of large pages. ASCII lowercasing
+    // keeps byte offsets valid for `html`.
+    let head_end = html.to_ascii_lowercase().find("</head>").map(|i| i + "</head>".len()).unwrap_or(html.len());
+    let document = kuchiki::parse_html().one(&html[..head_end]);
+
+    let title = document
+        .select_first("title")
+        .ok()
+        .map(|t| t.text_contents().trim().to_string())
+        .filter(|t| !t.is_empty());
+    let mut meta = HashMap::new();
+    if let Ok(nodes) = document.select("meta[content]") {
+        for node in nodes {
+            let attrs = node.attributes.borrow();
+            let Some(key) = attrs.get("name").or_else(|| attrs.get("property")) else { continue };
+            meta.insert(key.to_lowercase(), attrs.get("content").unwrap_or_default().to_string());
+        }
+    }
+
+    HeadData { title, meta }
+}
+
+/// Sets the BM25 relevance of each URL to `query`, using its URL and head metadata as the document.
+fn score(urls: &mut [SeededUrl], query: &str) {
+    let stemmer = Stemmer::create(Algorithm::English);
+    let corpus: Vec<Vec<String>> = urls
+        .iter()
+        .map(|entry| {
+            let mut text = entry.url.clone();
+            if let Some(ref head) = entry.head {
+                text.push(' ');
+                text.push_str(head.title.as_deref().unwrap_or_default());
+                for key in ["description", "keywords", "og:title", "og:description"] {
+                    if let Some(value) = head.meta.get(key) {
+                        text.push(' ');
+                        text.push_str(value);
+                    }
+                }
+            }
+            tokenize(&text, Some(&stemmer))
+        })
+        .collect();
+
+    let scores = bm25_scores(&corpus, &tokenize(query, Some(&stemmer)));
+    for (entry, score) in urls.iter_mut().zip(scores) {
+        entry.relevance_score = Some(score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(document: Document) -> Vec<(String, Option<String>)> {
+        match document {
+            Document::Urls(entries) => entries.into_iter().map(|e| (e.url, e.lastmod)).collect(),
+            Document::Index(_) => panic!("Expected a URL list"),
+        }
+    }
+
+    #[test]
+    fn test_parse_sitemaps() {
+        let index = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <sitemap><loc>https://example.com/posts.xml.gz</loc></sitemap>
+            </sitemapindex>"#;
+        assert_eq!(
+            parse_document(index, "https://example.com/sitemap.xml").unwrap(),
+            Document::Index(vec!["https://example.com/posts.xml.gz".to_string()])
+        );
+
+        let urlset = r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <url><loc> https://example.com/a </loc><lastmod>2024-05-01</lastmod></url>
+                <url><loc>https://example.com/b</loc></url>
+            </urlset>"#;
+        assert_eq!(
+            urls(parse_document(urlset, "https://example.com/sitemap.xml").unwrap()),
+            vec![
+                ("https://example.com/a".to_string(), Some("2024-05-01".to_string())),
+                ("https://example.com/b".to_string(), None),
+            ]
+        );
+
+        assert!(parse_document("<html></html>", "https://example.com/").is_err());
+    }
+
+    #[test]
+    fn test_parse_feeds() {
+        let rss = r#"<rss version="2.0"><channel><title>Blog</title>
+                <item><link>https://example.com/post-1</link><pubDate>Wed, 01 May 2024 10:00:00 GMT</pubDate></item>
+            </channel></rss>"#;
+        assert_eq!(
+            urls(parse_document(rss, "https://example.com/feed").unwrap()),
+            vec![("https://example.com/post-1".to_string(), Some("Wed, 01 May 2024 10:00:00 GMT".to_string()))]
+        );
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+                <link rel="self" href="/atom.xml"/>
+                <entry><link rel="edit" href="/edit/1"/><link href="/post-2"/><updated>2024-05-02</updated></entry>
+            </feed>"#;
+        assert_eq!(
+            urls(parse_document(atom, "https://example.com/atom.xml").unwrap()),
+            vec![("https://example.com/post-2".to_string(), Some("2024-05-02".to_string()))]
+        );
+    }
+
+    #[test]
+    fn test_parse_head() {
+        let head = parse_head(
+            r#"<html><head><title> Async Rust </title>
+                <meta name="Description" content="Learn async">
+                <meta property="og:title" content="Async">
+            </head><body><title>Ignored</title></body></html>"#,
+        );
+        assert_eq!(head.title.as_deref(), Some("Async Rust"));
+        assert_eq!(head.meta["description"], "Learn async");
+        assert_eq!(head.meta["og:title"], "Async");
+
+        // Lowercasing `İ` takes more bytes, which must not shift the end of the head
+        let head = parse_head("<head><title>İİİ İstanbul</title></head>");
+        assert_eq!(head.title.as_deref(), Some("İİİ İstanbul"));
+    }
+
+    #[test]
+    fn test_gunzip_limit() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&[b'a'; 10_000]).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(gunzip(&compressed, 10_000).unwrap().len(), 10_000);
+        assert!(gunzip(&compressed, 9_999).is_err());
+    }
+
+    #[test]
+    fn test_origin_of() {
+        assert_eq!(origin_of("example.com").unwrap(), "https://example.com");
+        assert_eq!(origin_of("http://localhost:8080/").unwrap(), "http://localhost:8080");
+        assert!(origin_of("ftp://example.com").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use crawl_4ai_rs::adaptive_crawler::{AdaptiveConfig, AdaptiveCrawler, StopReason};
+use crawl_4ai_rs::crawler::AsyncWebCrawler;
+use crawl_4ai_rs::models::{CrawlerStrategy, HttpCrawlerConfig};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Serves a start page linking to two pages about the query and one unrelated page.
+async fn mock_site() -> MockServer {
+    let mock_server = MockServer::start().await;
+
+    let pages = [
+        (
+            "/",
+            r#"<h1>Welcome</h1><p>Guides for our users, covering installation, configuration and deployment.</p>
+               <a href="/about">About us</a><a href="/tokio">Tokio runtime</a><a href="/tasks">Runtime tasks</a>"#,
+        ),
+        ("/tokio", "<p>The tokio runtime drives async tasks to completion.</p>"),
+        ("/tasks", "<p>Tasks are scheduled by the tokio runtime.</p>"),
+        ("/about", "<p>We are a small team.</p>"),
+    ];
+    for (page, body) in pages {
+        Mock::given(method("GET"))
+            .and(path(page))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("<html><body>{}</body></html>", body)))
+            .mount(&mock_server)
+            .await;
+    }
+
+    mock_server
+}
+
+#[tokio::test]
+async fn test_adaptive_crawl_stops_when_confident() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = AsyncWebCrawler::new().with_strategy(CrawlerStrategy::Http(HttpCrawlerConfig::default()));
+
+    let adaptive = AdaptiveCrawler::new(&crawler, AdaptiveConfig::default());
+    let state = adaptive.digest(&format!("{}/", base), "tokio runtime").await.unwrap();
+
+    let crawled: Vec<_> = state.pages.iter().map(|p| p.url.replacen(&base, "", 1)).collect();
+    assert_eq!(crawled, vec!["/", "/tokio", "/tasks"]);
+    assert_eq!(state.stop_reason, Some(StopReason::ConfidenceReached));
+    assert!(state.confidence() >= 0.7);
+
+    // The start page only mentions the query in its links
+    let relevant = state.relevant_pages(2);
+    assert_eq!(relevant.len(), 2);
+    assert_ne!(relevant[0].0.url, format!("{}/", base));
+    assert!(relevant[0].1 >= relevant[1].1);
+}
+
+#[tokio::test]
+async fn test_adaptive_crawl_respects_max_pages() {
+    let server = mock_site().await;
+    let crawler = AsyncWebCrawler::new().with_strategy(CrawlerStrategy::Http(HttpCrawlerConfig::default()));
+
+    let config = AdaptiveConfig {
+        max_pages: 2,
+        confidence_threshold: 0.95,
+        ..Default::default()
+    };
+    let state = AdaptiveCrawler::new(&crawler, config)
+        .digest(&server.uri(), "tokio runtime")
+        .await
+        .unwrap();
+
+    assert_eq!(state.pages.len(), 2);
+    assert_eq!(state.pages[1].url, format!("{}/tokio", server.uri()));
+    assert_eq!(state.stop_reason, Some(StopReason::MaxPages));
+}
@@ -0,0 +1,74 @@
+use crawl_4ai_rs::crawler::AsyncWebCrawler;
+use crawl_4ai_rs::models::DispatcherConfig;
+use futures::StreamExt;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_pages(mock_server: &MockServer) {
+    for page in ["one", "two", "three"] {
+        Mock::given(method("GET"))
+            .and(path(format!("/{}", page)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(format!("<html><body><p>Page {}</p></body></html>", page)),
+            )
+            .mount(mock_server)
+            .await;
+    }
+
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_arun_many_preserves_order() {
+    let mock_server = MockServer::start().await;
+    mount_pages(&mock_server).await;
+
+    let urls: Vec<String> = ["one", "missing", "two", "three"]
+        .iter()
+        .map(|p| format!("{}/{}", mock_server.uri(), p))
+        .collect();
+
+    let crawler = AsyncWebCrawler::new();
+    let results = crawler
+        .arun_many(urls.clone(), None, Some(DispatcherConfig::Semaphore { max_concurrent: 2 }))
+        .await
+        .expect("Batch crawl should start");
+
+    assert_eq!(results.len(), 4);
+    for (result, url) in results.iter().zip(&urls) {
+        assert_eq!(&result.url, url);
+    }
+
+    assert!(results[0].success);
+    assert!(results[0].html.contains("Page one"));
+    assert!(!results[1].success, "404 page should be reported as a failed result");
+    assert!(results[1].error_message.is_some());
+    assert!(results[2].success);
+    assert!(results[3].success);
+}
+
+#[tokio::test]
+async fn test_arun_many_stream() {
+    let mock_server = MockServer::start().await;
+    mount_pages(&mock_server).await;
+
+    let urls: Vec<String> = ["one", "two", "three"]
+        .iter()
+        .map(|p| format!("{}/{}", mock_server.uri(), p))
+        .collect();
+
+    let crawler = AsyncWebCrawler::new();
+    let stream = crawler
+        .arun_many_stream(urls, None, None)
+        .await
+        .expect("Batch crawl should start");
+    let results: Vec<_> = stream.collect().await;
+
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|r| r.success));
+}
@@ -8,7 +8,7 @@ async fn test_crawler_initialization() {
 
 #[tokio::test]
 async fn test_crawl_html() {
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let url = "https://example.com";
     let result = crawler.arun(url, None).await;
 
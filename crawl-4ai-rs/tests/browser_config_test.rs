@@ -0,0 +1,52 @@
+use crawl_4ai_rs::crawler::AsyncWebCrawler;
+use crawl_4ai_rs::models::BrowserConfig;
+use std::path::PathBuf;
+
+#[test]
+fn test_browser_config_defaults() {
+    let config = BrowserConfig::default();
+    assert!(config.headless);
+    assert!(!config.sandbox);
+    assert_eq!(config.viewport_width, 1080);
+    assert_eq!(config.viewport_height, 600);
+    assert!(config.extra_args.is_empty());
+    assert!(config.executable_path.is_none());
+}
+
+#[test]
+fn test_browser_config_partial_json() {
+    // Missing fields fall back to their defaults.
+    let json = r#"{
+        "headless": false,
+        "viewport_width": 1920,
+        "user_agent": "MyBot/1.0",
+        "extra_args": ["--disable-extensions"],
+        "locale": "de-DE",
+        "timezone": "Europe/Berlin",
+        "executable_path": "/opt/chrome/chrome"
+    }"#;
+
+    let config: BrowserConfig = serde_json::from_str(json).unwrap();
+    assert!(!config.headless);
+    assert_eq!(config.viewport_width, 1920);
+    assert_eq!(config.viewport_height, 600);
+    assert_eq!(config.user_agent.as_deref(), Some("MyBot/1.0"));
+    assert_eq!(config.extra_args, vec!["--disable-extensions".to_string()]);
+    assert_eq!(config.locale.as_deref(), Some("de-DE"));
+    assert_eq!(config.timezone.as_deref(), Some("Europe/Berlin"));
+    assert_eq!(config.executable_path, Some(PathBuf::from("/opt/chrome/chrome")));
+    assert!(config.user_data_dir.is_none());
+}
+
+#[test]
+fn test_crawler_with_config() {
+    let config = BrowserConfig {
+        headless: false,
+        user_data_dir: Some(PathBuf::from("/tmp/crawl4ai-profile")),
+        ..Default::default()
+    };
+
+    let crawler = AsyncWebCrawler::with_config(config);
+    assert!(!crawler.config().headless);
+    assert_eq!(crawler.config().user_data_dir, Some(PathBuf::from("/tmp/crawl4ai-profile")));
+}
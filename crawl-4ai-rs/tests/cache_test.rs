@@ -0,0 +1,132 @@
+use crawl_4ai_rs::cache::CrawlCache;
+use crawl_4ai_rs::crawler::AsyncWebCrawler;
+use crawl_4ai_rs::extraction_strategy::JsonCssExtractionStrategy;
+use crawl_4ai_rs::models::{
+    BrowserConfig, CacheMode, CrawlResult, CrawlerRunConfig, CrawlerStrategy, ExtractionStrategyConfig, HttpCrawlerConfig,
+};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn temp_cache(name: &str) -> CrawlCache {
+    let dir = std::env::temp_dir().join(format!("crawl4ai-{}-{}", name, std::process::id()));
+    CrawlCache::new(dir)
+}
+
+#[tokio::test]
+async fn test_cached_result_is_reprocessed_without_browser() {
+    let mock_server = MockServer::start().await;
+
+    // The page must be served from the cache, not fetched
+    Mock::given(method("GET"))
+        .and(path("/products"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Live page"))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/products", mock_server.uri());
+    let cache = temp_cache("reprocess");
+
+    let cached = CrawlResult {
+        url: url.clone(),
+        html: r#"<html><body><div class="product"><h2>Cached Product</h2></div></body></html>"#.to_string(),
+        success: true,
+        ..Default::default()
+    };
+    cache.set(&CrawlCache::cache_key(&url, &None, &BrowserConfig::default(), &CrawlerStrategy::Browser), &cached).await.unwrap();
+
+    let crawler = AsyncWebCrawler::new().with_cache(cache.clone());
+    let config = CrawlerRunConfig {
+        cache_mode: CacheMode::Enabled,
+        extraction_strategy: Some(ExtractionStrategyConfig::JsonCss(JsonCssExtractionStrategy::new(json!({
+            "baseSelector": ".product",
+            "fields": [{"name": "name", "selector": "h2", "type": "text"}]
+        })))),
+        ..Default::default()
+    };
+
+    let result = crawler.arun(&url, Some(config)).await.expect("Cache hit should not need a browser");
+
+    assert!(result.html.contains("Cached Product"));
+    assert!(result.markdown.unwrap().raw_markdown.contains("Cached Product"));
+    let extracted: serde_json::Value = serde_json::from_str(&result.extracted_content.unwrap()).unwrap();
+    assert_eq!(extracted[0]["name"], "Cached Product");
+
+    cache.clear().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_write_only_caches_fresh_result() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/fresh"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html><body>Fresh</body></html>"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/fresh", mock_server.uri());
+    let cache = temp_cache("write-only");
+    let crawler = AsyncWebCrawler::new().with_cache(cache.clone());
+
+    let write = CrawlerRunConfig {
+        cache_mode: CacheMode::WriteOnly,
+        ..Default::default()
+    };
+    let result = crawler.arun(&url, Some(write)).await;
+    assert!(result.is_ok());
+
+    let key = CrawlCache::cache_key(&url, &None, &BrowserConfig::default(), &CrawlerStrategy::Browser);
+    assert!(cache.get(&key).await.unwrap().html.contains("Fresh"));
+
+    // A read-only run is now served from the cache (the mock expects a single request)
+    let read = CrawlerRunConfig {
+        cache_mode: CacheMode::ReadOnly,
+        ..Default::default()
+    };
+    let result = crawler.arun(&url, Some(read)).await;
+    assert!(result.unwrap().html.contains("Fresh"));
+
+    cache.clear().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_strategies_and_headers_do_not_share_entries() {
+    let mock_server = MockServer::start().await;
+
+    // Fetched once anonymously and once with credentials
+    Mock::given(method("GET"))
+        .and(path("/account"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html><body>Account</body></html>"))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/account", mock_server.uri());
+    let cache = temp_cache("strategies");
+    let anonymous = HttpCrawlerConfig::default();
+    let mut logged_in = HttpCrawlerConfig::default();
+    logged_in.headers.insert("Authorization".to_string(), "Bearer secret".to_string());
+
+    let config = CrawlerRunConfig {
+        cache_mode: CacheMode::Enabled,
+        ..Default::default()
+    };
+    for http in [logged_in, anonymous.clone()] {
+        let crawler = AsyncWebCrawler::new()
+            .with_strategy(CrawlerStrategy::Http(http))
+            .with_cache(cache.clone());
+        crawler.arun(&url, Some(config.clone())).await.unwrap();
+    }
+
+    // The plain HTTP entry is not served to browser crawls
+    let browser = BrowserConfig::default();
+    let http_key = CrawlCache::cache_key(&url, &Some(config.clone()), &browser, &CrawlerStrategy::Http(anonymous));
+    let browser_key = CrawlCache::cache_key(&url, &Some(config), &browser, &CrawlerStrategy::Browser);
+    assert!(cache.get(&http_key).await.is_some());
+    assert!(cache.get(&browser_key).await.is_none());
+
+    cache.clear().await.unwrap();
+}
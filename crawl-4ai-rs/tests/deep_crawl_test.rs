@@ -0,0 +1,321 @@
+use crawl_4ai_rs::crawler::AsyncWebCrawler;
+use crawl_4ai_rs::deep_crawl::checkpoint::{CheckpointConfig, CrawlCheckpoint, UrlStatus};
+use crawl_4ai_rs::deep_crawl::filters::{ContentTypeFilter, FilterChain, UrlFilter, UrlPatternFilter};
+use crawl_4ai_rs::deep_crawl::scorers::{KeywordRelevanceScorer, UrlScorerConfig};
+use crawl_4ai_rs::deep_crawl::{DeepCrawlConfig, DeepCrawlStrategy};
+use crawl_4ai_rs::models::{BrowserConfig, CrawlResult, CrawlerRunConfig, CrawlerStrategy, HttpCrawlerConfig, Link};
+use futures::StreamExt;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Serves a small site:
+///
+/// ```text
+/// /  -> /a, /b, https://external.example/
+/// /a -> /a1, /b#section (duplicate of /b)
+/// /b -> /b1
+/// ```
+async fn mock_site() -> MockServer {
+    let mock_server = MockServer::start().await;
+
+    let pages = [
+        ("/", r#"<a href="/a">A</a><a href="/b/">B</a><a href="https://external.example/">Ext</a>"#),
+        ("/a", r#"<a href="/a1">A1</a><a href="/b#section">B again</a>"#),
+        ("/b", r#"<a href="b1">B1</a>"#),
+        ("/a1", "<p>Leaf A1</p>"),
+        ("/b1", "<p>Leaf B1</p>"),
+    ];
+    for (page, body) in pages {
+        Mock::given(method("GET"))
+            .and(path(page))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("<html><body>{}</body></html>", body)))
+            .mount(&mock_server)
+            .await;
+    }
+
+    mock_server
+}
+
+fn http_crawler() -> AsyncWebCrawler {
+    AsyncWebCrawler::new().with_strategy(CrawlerStrategy::Http(HttpCrawlerConfig::default()))
+}
+
+async fn crawl(crawler: &AsyncWebCrawler, start: &str, config: DeepCrawlConfig) -> Vec<CrawlResult> {
+    crawler.adeep_crawl(start, None, config).collect().await
+}
+
+fn paths(results: &[CrawlResult], base: &str) -> Vec<String> {
+    results.iter().map(|r| r.url.replacen(base, "", 1)).collect()
+}
+
+#[tokio::test]
+async fn test_bfs_visits_levels_in_order() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = http_crawler();
+
+    let results = crawl(&crawler, &format!("{}/", base), DeepCrawlConfig::default()).await;
+
+    assert_eq!(paths(&results, &base), vec!["/", "/a", "/b", "/a1", "/b1"]);
+    assert!(results.iter().all(|r| r.success));
+
+    let depths: Vec<_> = results.iter().map(|r| r.depth.unwrap()).collect();
+    assert_eq!(depths, vec![0, 1, 1, 2, 2]);
+    assert_eq!(results[0].parent_url, None);
+    assert_eq!(results[4].parent_url, Some(format!("{}/b", base)));
+}
+
+#[tokio::test]
+async fn test_links_resolve_against_redirected_url() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/docs"))
+        .respond_with(ResponseTemplate::new(301).insert_header("location", "/docs/"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/docs/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"<a href="guide">Guide</a>"#))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/docs/guide"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Guide</p>"))
+        .mount(&server)
+        .await;
+
+    let base = server.uri();
+    let results = crawl(&http_crawler(), &format!("{}/docs", base), DeepCrawlConfig::default()).await;
+
+    assert_eq!(paths(&results, &base), vec!["/docs", "/docs/guide"]);
+    // The parent is the URL that was crawled, not where it redirected to
+    assert_eq!(results[1].parent_url, Some(format!("{}/docs", base)));
+}
+
+#[tokio::test]
+async fn test_dfs_follows_first_link_first() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = http_crawler();
+
+    let config = DeepCrawlConfig {
+        strategy: DeepCrawlStrategy::Dfs,
+        ..Default::default()
+    };
+    let results = crawl(&crawler, &format!("{}/", base), config).await;
+
+    assert_eq!(paths(&results, &base), vec!["/", "/a", "/a1", "/b", "/b1"]);
+}
+
+#[tokio::test]
+async fn test_max_depth_and_max_pages() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = http_crawler();
+
+    let shallow = DeepCrawlConfig {
+        max_depth: 1,
+        ..Default::default()
+    };
+    assert_eq!(paths(&crawl(&crawler, &base, shallow).await, &base), vec!["/", "/a", "/b"]);
+
+    let limited = DeepCrawlConfig {
+        max_pages: Some(2),
+        strategy: DeepCrawlStrategy::BestFirst,
+        ..Default::default()
+    };
+    assert_eq!(paths(&crawl(&crawler, &base, limited).await, &base), vec!["/", "/a"]);
+}
+
+#[tokio::test]
+async fn test_include_external() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = http_crawler();
+
+    let config = DeepCrawlConfig {
+        max_depth: 1,
+        include_external: true,
+        ..Default::default()
+    };
+    let results = crawl(&crawler, &base, config).await;
+
+    // The external page cannot be reached, so it is reported as a failure
+    let external = results.iter().find(|r| r.url == "https://external.example/").unwrap();
+    assert!(!external.success);
+    assert_eq!(external.depth, Some(1));
+    assert_eq!(results.len(), 4);
+}
+
+#[tokio::test]
+async fn test_filter_chain_skips_rejected_urls() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = http_crawler();
+
+    let mut pattern = UrlPatternFilter::new(vec!["/b*".to_string()]);
+    pattern.reverse = true;
+    let filter = UrlFilter::UrlPattern(pattern);
+    let config = DeepCrawlConfig {
+        filter_chain: Some(FilterChain::new(vec![filter.clone()])),
+        ..Default::default()
+    };
+    let results = crawl(&crawler, &base, config).await;
+
+    assert_eq!(paths(&results, &base), vec!["/", "/a", "/a1"]);
+    // `/b` is linked twice but only checked once
+    assert_eq!(filter.stats().total(), 3);
+    assert_eq!(filter.stats().passed(), 2);
+    assert_eq!(filter.stats().rejected(), 1);
+}
+
+#[tokio::test]
+async fn test_filter_requests_use_crawler_identity() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = AsyncWebCrawler::with_config(BrowserConfig {
+        user_agent: Some("filter-test-bot".to_string()),
+        ..Default::default()
+    })
+    .with_strategy(CrawlerStrategy::Http(HttpCrawlerConfig::default()));
+
+    let filter = ContentTypeFilter {
+        check_extension: false,
+        check_head: true,
+        ..ContentTypeFilter::new(vec!["text/html".to_string()])
+    };
+    let config = DeepCrawlConfig {
+        max_depth: 1,
+        filter_chain: Some(FilterChain::new(vec![UrlFilter::ContentType(filter)])),
+        ..Default::default()
+    };
+    crawl(&crawler, &base, config).await;
+
+    let requests = server.received_requests().await.unwrap();
+    let heads: Vec<_> = requests.iter().filter(|r| r.method.as_str() == "HEAD").collect();
+    assert_eq!(heads.len(), 2);
+    assert!(heads.iter().all(|r| r.headers.get("user-agent").unwrap() == "filter-test-bot"));
+}
+
+#[tokio::test]
+async fn test_filter_requests_respect_robots_txt() {
+    let server = mock_site().await;
+    let base = server.uri();
+    Mock::given(method("GET"))
+        .and(path("/robots.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /b\n"))
+        .mount(&server)
+        .await;
+    let crawler = http_crawler();
+
+    let filter = ContentTypeFilter {
+        check_extension: false,
+        check_head: true,
+        ..ContentTypeFilter::new(vec!["text/html".to_string()])
+    };
+    let config = DeepCrawlConfig {
+        max_depth: 1,
+        filter_chain: Some(FilterChain::new(vec![UrlFilter::ContentType(filter)])),
+        ..Default::default()
+    };
+    let run_config = CrawlerRunConfig {
+        check_robots_txt: true,
+        ..Default::default()
+    };
+    let results: Vec<CrawlResult> = crawler.adeep_crawl(&base, Some(run_config), config).collect().await;
+
+    assert_eq!(paths(&results, &base), vec!["/", "/a"]);
+    // The disallowed page is not even checked with a HEAD request
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| !r.url.path().starts_with("/b")));
+}
+
+#[tokio::test]
+async fn test_best_first_follows_relevant_links() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = http_crawler();
+
+    let config = DeepCrawlConfig {
+        strategy: DeepCrawlStrategy::BestFirst,
+        max_pages: Some(2),
+        url_scorer: Some(UrlScorerConfig::KeywordRelevance(KeywordRelevanceScorer::new(vec!["b".to_string()]))),
+        ..Default::default()
+    };
+    let results = crawl(&crawler, &base, config).await;
+
+    // Without a scorer the budget would go to `/a`, the first link
+    assert_eq!(paths(&results, &base), vec!["/", "/b"]);
+}
+
+#[tokio::test]
+async fn test_best_first_with_custom_scorer() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = http_crawler();
+
+    let config = DeepCrawlConfig {
+        strategy: DeepCrawlStrategy::BestFirst,
+        max_pages: Some(2),
+        ..Default::default()
+    }
+    .with_url_scorer(|link: &Link| if link.text.as_deref() == Some("B") { 1.0 } else { 0.0 });
+    let results = crawl(&crawler, &base, config).await;
+
+    assert_eq!(paths(&results, &base), vec!["/", "/b"]);
+}
+
+#[tokio::test]
+async fn test_checkpoint_resume_skips_completed_urls() {
+    let server = mock_site().await;
+    let base = server.uri();
+    let crawler = http_crawler();
+    let dir = std::env::temp_dir().join(format!("crawl4ai-deep-checkpoint-{}", std::process::id()));
+    let checkpoint = CheckpointConfig {
+        interval: 1,
+        ..CheckpointConfig::new(dir.join("state.json"))
+    };
+
+    // Stop early, as if the crawl had died after two pages
+    let interrupted = DeepCrawlConfig {
+        max_pages: Some(2),
+        checkpoint: Some(checkpoint.clone()),
+        ..Default::default()
+    };
+    assert_eq!(paths(&crawl(&crawler, &base, interrupted).await, &base), vec!["/", "/a"]);
+
+    let saved = CrawlCheckpoint::load(&checkpoint.path).await.unwrap();
+    assert_eq!(saved.pages_crawled, 2);
+    assert_eq!(saved.statuses[&format!("{}/a", base)], UrlStatus::Succeeded);
+    assert_eq!(saved.statuses[&format!("{}/a1", base)], UrlStatus::Queued);
+    assert_eq!(saved.depths[&format!("{}/a1", base)], 2);
+
+    // Another strategy does not pick up the state, and neither does a crawl without `resume`
+    let dfs = DeepCrawlConfig {
+        strategy: DeepCrawlStrategy::Dfs,
+        max_pages: Some(1),
+        checkpoint: Some(checkpoint.clone().with_resume()),
+        ..Default::default()
+    };
+    assert_eq!(paths(&crawl(&crawler, &base, dfs).await, &base), vec!["/"]);
+    let fresh = DeepCrawlConfig {
+        max_pages: Some(2),
+        checkpoint: Some(checkpoint.clone()),
+        ..Default::default()
+    };
+    assert_eq!(paths(&crawl(&crawler, &base, fresh).await, &base), vec!["/", "/a"]);
+
+    let resumed = DeepCrawlConfig {
+        checkpoint: Some(checkpoint.clone().with_resume()),
+        ..Default::default()
+    };
+    let results = crawl(&crawler, &base, resumed).await;
+    assert_eq!(paths(&results, &base), vec!["/b", "/a1", "/b1"]);
+    assert_eq!(results[0].depth, Some(1));
+
+    let finished = CrawlCheckpoint::load(&checkpoint.path).await.unwrap();
+    assert!(finished.is_finished());
+    assert_eq!(finished.status_counts()[&UrlStatus::Succeeded], 5);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
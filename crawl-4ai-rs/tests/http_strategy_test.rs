@@ -0,0 +1,337 @@
+use crawl_4ai_rs::crawler::{AsyncWebCrawler, CrawlerError};
+use crawl_4ai_rs::extraction_strategy::JsonCssExtractionStrategy;
+use crawl_4ai_rs::models::{BrowserConfig, CrawlerRunConfig, CrawlerStrategy, ExtractionStrategyConfig, HttpCrawlerConfig};
+use crawl_4ai_rs::rate_limiter::RateLimiter;
+use serde_json::json;
+use std::collections::HashMap;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn http_crawler() -> AsyncWebCrawler {
+    AsyncWebCrawler::new().with_strategy(CrawlerStrategy::Http(HttpCrawlerConfig::default()))
+}
+
+#[tokio::test]
+async fn test_http_strategy_full_pipeline() {
+    let mock_server = MockServer::start().await;
+
+    let html = r#"
+        <html lang="en"><head><title>Catalog</title><meta property="og:type" content="website"></head><body>
+            <h1>Catalog</h1>
+            <div class="product"><h2>Widget</h2><img src="/img/widget.png" alt="Widget"></div>
+            <a href="/next">Next page</a>
+            <a href="https://example.org/">Partner</a>
+        </body></html>
+    "#;
+
+    Mock::given(method("GET"))
+        .and(path("/catalog"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(html))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let crawler = http_crawler();
+    let config = CrawlerRunConfig {
+        extraction_strategy: Some(ExtractionStrategyConfig::JsonCss(JsonCssExtractionStrategy::new(json!({
+            "baseSelector": ".product",
+            "fields": [{"name": "name", "selector": "h2", "type": "text"}]
+        })))),
+        ..Default::default()
+    };
+    let url = format!("{}/catalog", mock_server.uri());
+
+    let result = crawler.arun(&url, Some(config)).await.expect("HTTP crawl should not need a browser");
+
+    assert!(result.success);
+    assert!(result.html.contains("Catalog"));
+    let markdown = result.markdown.unwrap();
+    assert!(markdown.raw_markdown.contains("Widget"));
+    assert!(markdown.markdown_with_citations.contains("Next page⟨2⟩"));
+    assert!(markdown.references_markdown.contains(&format!("⟨2⟩ {}/next: Next page", mock_server.uri())));
+
+    let extracted: serde_json::Value = serde_json::from_str(&result.extracted_content.unwrap()).unwrap();
+    assert_eq!(extracted[0]["name"], "Widget");
+
+    let links = result.links.unwrap();
+    assert_eq!(links["internal"][0].href, Some(format!("{}/next", mock_server.uri())));
+    assert_eq!(links["external"][0].href.as_deref(), Some("https://example.org/"));
+
+    let metadata = result.metadata.unwrap();
+    assert_eq!(metadata.title.as_deref(), Some("Catalog"));
+    assert_eq!(metadata.language.as_deref(), Some("en"));
+    assert_eq!(metadata.open_graph["og:type"], "website");
+
+    let media = result.media.unwrap();
+    assert_eq!(media["images"][0].src, Some(format!("{}/img/widget.png", mock_server.uri())));
+}
+
+#[tokio::test]
+async fn test_http_strategy_sends_configured_headers() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/headers"))
+        .and(header("user-agent", "TestAgent/1.0"))
+        .and(header("accept-language", "de-DE"))
+        .and(header("x-api-key", "secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>ok</p>"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let browser_config = BrowserConfig {
+        user_agent: Some("TestAgent/1.0".to_string()),
+        locale: Some("de-DE".to_string()),
+        ..Default::default()
+    };
+    let http_config = HttpCrawlerConfig {
+        headers: HashMap::from([("X-Api-Key".to_string(), "secret".to_string())]),
+        ..Default::default()
+    };
+    let crawler = AsyncWebCrawler::with_config(browser_config).with_strategy(CrawlerStrategy::Http(http_config));
+
+    let result = crawler.arun(&format!("{}/headers", mock_server.uri()), None).await;
+    assert!(result.is_ok(), "Request should match the header expectations: {:?}", result.err());
+}
+
+#[tokio::test]
+async fn test_http_strategy_404_is_not_retried() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let crawler = http_crawler();
+    let result = crawler.arun(&format!("{}/missing", mock_server.uri()), None).await;
+
+    match result.unwrap_err().downcast_ref::<CrawlerError>() {
+        Some(CrawlerError::HttpStatusCode(code)) => assert_eq!(*code, 404),
+        other => panic!("Expected HttpStatusCode error, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_http_strategy_rate_limit_and_batch() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/limited"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Page</p>"))
+        .mount(&mock_server)
+        .await;
+
+    let crawler = http_crawler().with_rate_limiter(RateLimiter::new((0, 0), 1_000, 3));
+    let urls: Vec<String> = ["/limited", "/a", "/b"]
+        .iter()
+        .map(|p| format!("{}{}", mock_server.uri(), p))
+        .collect();
+
+    let results = crawler.arun_many(urls.clone(), None, None).await.unwrap();
+
+    assert_eq!(results.len(), 3);
+    for (result, url) in results.iter().zip(&urls) {
+        assert!(result.success, "{} failed: {:?}", url, result.error_message);
+        assert_eq!(&result.url, url);
+    }
+}
+
+#[tokio::test]
+async fn test_http_strategy_rate_limit_retries_are_bounded_per_call() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/limited"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Page</p>"))
+        .mount(&mock_server)
+        .await;
+
+    // Successful crawls of the same domain keep resetting its failure count
+    let crawler = http_crawler().with_rate_limiter(RateLimiter::new((0, 0), 50, 2));
+    let mut urls = vec![format!("{}/limited", mock_server.uri())];
+    urls.extend((0..20).map(|i| format!("{}/page{}", mock_server.uri(), i)));
+
+    let results = crawler.arun_many(urls, None, None).await.unwrap();
+
+    assert!(!results[0].success);
+    let requests = mock_server.received_requests().await.unwrap();
+    let limited = requests.iter().filter(|r| r.url.path() == "/limited").count();
+    assert!(limited <= 3, "/limited was requested {} times", limited);
+}
+
+#[tokio::test]
+async fn test_http_strategy_records_redirects_and_status() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/old"))
+        .respond_with(ResponseTemplate::new(301).insert_header("Location", "/moved"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/moved"))
+        .respond_with(ResponseTemplate::new(302).insert_header("Location", format!("{}/new", mock_server.uri()).as_str()))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/new"))
+        .respond_with(
+            ResponseTemplate::new(203)
+                .insert_header("X-Cache", "HIT")
+                .set_body_string(r#"<a href="next">Next</a>"#),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let crawler = http_crawler();
+    let result = crawler.arun(&format!("{}/old", mock_server.uri()), None).await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.url, format!("{}/old", mock_server.uri()));
+    assert_eq!(result.status_code, Some(203));
+    assert_eq!(result.redirected_url, Some(format!("{}/new", mock_server.uri())));
+    assert_eq!(result.response_headers.unwrap()["x-cache"], "HIT");
+
+    let chain: Vec<(String, u16)> = result.redirect_chain.into_iter().map(|r| (r.url, r.status_code)).collect();
+    assert_eq!(chain, vec![
+        (format!("{}/old", mock_server.uri()), 301),
+        (format!("{}/moved", mock_server.uri()), 302),
+    ]);
+    // Links resolve against the final URL
+    assert_eq!(result.links.unwrap()["internal"][0].href, Some(format!("{}/next", mock_server.uri())));
+}
+
+#[tokio::test]
+async fn test_http_strategy_redirect_limit() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/loop"))
+        .respond_with(ResponseTemplate::new(302).insert_header("Location", "/loop"))
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new().with_strategy(CrawlerStrategy::Http(HttpCrawlerConfig {
+        max_redirects: 2,
+        ..Default::default()
+    }));
+    let result = crawler.arun(&format!("{}/loop", mock_server.uri()), None).await;
+
+    match result.unwrap_err().downcast_ref::<CrawlerError>() {
+        Some(CrawlerError::NavigationError(message)) => assert!(message.contains("Too many redirects"), "{}", message),
+        other => panic!("Expected NavigationError, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_http_strategy_keeps_credentials_on_origin() {
+    let site = MockServer::start().await;
+    let other = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/login"))
+        .and(header("authorization", "Bearer secret"))
+        .respond_with(ResponseTemplate::new(302).insert_header("Location", format!("{}/landing", other.uri())))
+        .mount(&site)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/landing"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Landing</p>"))
+        .mount(&other)
+        .await;
+
+    let http_config = HttpCrawlerConfig {
+        headers: HashMap::from([
+            ("Authorization".to_string(), "Bearer secret".to_string()),
+            ("X-Api-Key".to_string(), "public".to_string()),
+        ]),
+        ..Default::default()
+    };
+    let crawler = AsyncWebCrawler::new().with_strategy(CrawlerStrategy::Http(http_config));
+    let result = crawler.arun(&format!("{}/login", site.uri()), None).await.unwrap();
+    assert!(result.html.contains("Landing"));
+
+    let requests = other.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].headers.contains_key("authorization"));
+    assert_eq!(requests[0].headers.get("x-api-key").unwrap(), "public");
+}
+
+#[tokio::test]
+async fn test_http_strategy_refuses_binary_and_oversized_bodies() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/paper.pdf"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(b"%PDF-1.7".to_vec(), "application/pdf"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/large"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!("<p>{}</p>", "x".repeat(2048))))
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new().with_strategy(CrawlerStrategy::Http(HttpCrawlerConfig {
+        max_body_bytes: 1024,
+        ..Default::default()
+    }));
+    for (page, expected) in [("/paper.pdf", "Not an HTML page"), ("/large", "larger than 1024 bytes")] {
+        let result = crawler.arun(&format!("{}{}", mock_server.uri(), page), None).await;
+        match result.unwrap_err().downcast_ref::<CrawlerError>() {
+            Some(CrawlerError::NavigationError(message)) => assert!(message.contains(expected), "{}", message),
+            other => panic!("Expected NavigationError, got: {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_css_selector_links_resolve_against_effective_base() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/old"))
+        .respond_with(ResponseTemplate::new(301).insert_header("Location", "/docs/new"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/docs/new"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<html><head><title>Docs</title></head><body><div id="main"><p>Read the <a href="guide">guide</a>.</p></div></body></html>"#,
+        ))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/based"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<html><head><base href="/static/"></head><body><div id="main"><p>See the <a href="faq">FAQ</a>.</p></div></body></html>"#,
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let base = mock_server.uri();
+    let config = CrawlerRunConfig {
+        css_selector: Some("#main".to_string()),
+        ..Default::default()
+    };
+    for (page, expected) in [("/old", "/docs/guide"), ("/based", "/static/faq")] {
+        let result = http_crawler().arun(&format!("{}{}", base, page), Some(config.clone())).await.unwrap();
+
+        let expected = format!("{}{}", base, expected);
+        let links = result.links.unwrap();
+        assert_eq!(links["internal"][0].href.as_deref(), Some(expected.as_str()));
+        assert!(result.markdown.unwrap().references_markdown.contains(&expected));
+    }
+}
@@ -0,0 +1,72 @@
+use crawl_4ai_rs::crawler::{AsyncWebCrawler, CrawlerError};
+use crawl_4ai_rs::models::{CrawlerRunConfig, WaitStrategy};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const PAGE: &str = r#"<html><body>
+    <ul id="items"><li>Item 1</li></ul>
+    <button id="more" onclick="const li = document.createElement('li'); li.textContent = 'Item ' + (items.children.length + 1); items.appendChild(li);">Load more</button>
+</body></html>"#;
+
+#[tokio::test]
+async fn test_js_code_and_js_only() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/list"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(PAGE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new();
+    let url = format!("{}/list", mock_server.uri());
+
+    let config = CrawlerRunConfig {
+        session_id: Some("js_session".to_string()),
+        js_code: vec![
+            "document.querySelector('#more').click()".to_string(),
+            "Promise.resolve(document.querySelectorAll('#items li').length)".to_string(),
+            "missingFunction()".to_string(),
+        ],
+        wait_for: Some(WaitStrategy::Selector("#items li:nth-child(2)".to_string())),
+        ..Default::default()
+    };
+    let result = crawler.arun(&url, Some(config)).await.expect("Crawl with js_code failed");
+
+    assert!(result.html.contains("Item 2"));
+    let js_results = result.js_execution_result.unwrap();
+    assert_eq!(js_results[1], json!(2));
+    assert!(js_results[2]["error"].is_string());
+
+    // Continue on the open page without fetching it again
+    let config = CrawlerRunConfig {
+        session_id: Some("js_session".to_string()),
+        js_only: true,
+        js_code: vec!["document.querySelector('#more').click()".to_string()],
+        ..Default::default()
+    };
+    let result = crawler.arun(&url, Some(config)).await.expect("js_only crawl failed");
+
+    assert!(result.html.contains("Item 3"));
+    assert!(result.status_code.is_none());
+}
+
+#[tokio::test]
+async fn test_js_only_needs_open_session_page() {
+    let crawler = AsyncWebCrawler::new();
+    let config = CrawlerRunConfig {
+        session_id: Some("never_opened".to_string()),
+        js_only: true,
+        js_code: vec!["1 + 1".to_string()],
+        ..Default::default()
+    };
+
+    let result = crawler.arun("https://example.com", Some(config)).await;
+
+    match result.unwrap_err().downcast_ref::<CrawlerError>() {
+        Some(CrawlerError::NavigationError(message)) => assert!(message.contains("js_only")),
+        other => panic!("Expected NavigationError, got: {:?}", other),
+    }
+}
@@ -0,0 +1,146 @@
+use crawl_4ai_rs::content_scraping::ContentScrapingStrategy;
+use crawl_4ai_rs::crawler::AsyncWebCrawler;
+use crawl_4ai_rs::extraction_strategy::JsonCssExtractionStrategy;
+use crawl_4ai_rs::models::{CrawlerRunConfig, ExtractionStrategyConfig, MarkdownSource};
+use serde_json::json;
+use url::Url;
+
+const PAGE: &str = r#"
+    <html><body>
+        <h1>Local Catalog</h1>
+        <div class="product"><h2>Gadget</h2><img src="gadget.png" alt="Gadget"></div>
+        <a href="page2.html">Next</a>
+        <a href="https://example.com/">Example</a>
+    </body></html>
+"#;
+
+fn extraction_config() -> CrawlerRunConfig {
+    CrawlerRunConfig {
+        extraction_strategy: Some(ExtractionStrategyConfig::JsonCss(JsonCssExtractionStrategy::new(json!({
+            "baseSelector": ".product",
+            "fields": [{"name": "name", "selector": "h2", "type": "text"}]
+        })))),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_raw_html_is_processed_without_browser() {
+    let crawler = AsyncWebCrawler::new();
+    let result = crawler
+        .arun(&format!("raw:{}", PAGE), Some(extraction_config()))
+        .await
+        .expect("Raw HTML should not need a browser");
+
+    assert!(result.success);
+    assert_eq!(result.html, PAGE);
+    assert!(result.markdown.unwrap().raw_markdown.contains("Local Catalog"));
+
+    let extracted: serde_json::Value = serde_json::from_str(&result.extracted_content.unwrap()).unwrap();
+    assert_eq!(extracted[0]["name"], "Gadget");
+
+    let links = result.links.unwrap();
+    assert_eq!(links["internal"][0].href.as_deref(), Some("page2.html"));
+    assert_eq!(links["external"][0].href.as_deref(), Some("https://example.com/"));
+    assert_eq!(result.media.unwrap()["images"][0].alt.as_deref(), Some("Gadget"));
+    assert!(result.metadata.is_some());
+}
+
+#[tokio::test]
+async fn test_file_url_resolves_relative_links() {
+    let dir = std::env::temp_dir().join(format!("crawl4ai-local-content-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("catalog.html");
+    std::fs::write(&path, PAGE).unwrap();
+    let url = Url::from_file_path(&path).unwrap().to_string();
+
+    let crawler = AsyncWebCrawler::new();
+    let results = crawler
+        .arun_many(vec![url.clone()], Some(extraction_config()), None)
+        .await
+        .expect("Local files should not need a browser");
+
+    let result = &results[0];
+    assert!(result.success, "{:?}", result.error_message);
+    assert_eq!(result.url, url);
+
+    let links = result.links.as_ref().unwrap();
+    let expected = Url::from_file_path(dir.join("page2.html")).unwrap().to_string();
+    assert_eq!(links["internal"][0].href.as_deref(), Some(expected.as_str()));
+
+    let missing = crawler.arun(&url.replace("catalog", "missing"), None).await;
+    assert!(missing.is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_markdown_from_cleaned_html() {
+    let html = r#"<html><body>
+        <nav><a href="/">Home</a> <a href="/docs">Docs</a></nav>
+        <main class="content"><h1>Guide</h1><p>Install the crate with cargo.</p><script>alert(1)</script></main>
+    </body></html>"#;
+    let crawler = AsyncWebCrawler::new();
+
+    let raw = crawler.arun(&format!("raw:{}", html), None).await.unwrap();
+    let cleaned_html = raw.cleaned_html.unwrap();
+    assert!(cleaned_html.contains("<h1>Guide</h1>"));
+    assert!(!cleaned_html.contains("script") && !cleaned_html.contains("class="));
+    assert!(raw.markdown.unwrap().raw_markdown.contains("Home"));
+
+    let config = CrawlerRunConfig {
+        scraping_strategy: Some(ContentScrapingStrategy {
+            excluded_tags: vec!["nav".to_string()],
+            ..Default::default()
+        }),
+        markdown_source: MarkdownSource::CleanedHtml,
+        ..Default::default()
+    };
+    let cleaned = crawler.arun(&format!("raw:{}", html), Some(config)).await.unwrap();
+    let markdown = cleaned.markdown.unwrap().raw_markdown;
+    assert!(markdown.contains("# Guide"));
+    assert!(markdown.contains("Install the crate with cargo."));
+    assert!(!markdown.contains("Home"));
+    // Links and media are still extracted from the raw HTML
+    assert_eq!(cleaned.links.unwrap()["internal"].len(), 2);
+}
+
+#[tokio::test]
+async fn test_css_selector_and_target_elements() {
+    let html = r#"<html><body>
+        <nav><a href="/home">Home</a></nav>
+        <main>
+            <article><h1>Story</h1><p class="lead">Read on.</p><a href="/story/2">Part 2</a></article>
+            <aside><p class="lead">Sidebar note</p><a href="/related">Related</a></aside>
+        </main>
+    </body></html>"#;
+    let url = format!("raw:{}", html);
+    let crawler = AsyncWebCrawler::new();
+    let extraction = Some(ExtractionStrategyConfig::JsonCss(JsonCssExtractionStrategy::new(json!({
+        "baseSelector": ".lead",
+        "fields": [{"name": "text", "type": "text"}]
+    }))));
+
+    let hard = CrawlerRunConfig {
+        css_selector: Some("main".to_string()),
+        ..Default::default()
+    };
+    let result = crawler.arun(&url, Some(hard)).await.unwrap();
+    assert_eq!(result.html, html);
+    assert!(!result.cleaned_html.unwrap().contains("Home"));
+    assert!(!result.markdown.unwrap().raw_markdown.contains("Home"));
+    let links: Vec<_> = result.links.unwrap()["internal"].iter().map(|l| l.href.clone().unwrap()).collect();
+    assert_eq!(links, vec!["/story/2", "/related"]);
+
+    let soft = CrawlerRunConfig {
+        target_elements: vec!["article".to_string()],
+        extraction_strategy: extraction,
+        ..Default::default()
+    };
+    let result = crawler.arun(&url, Some(soft)).await.unwrap();
+    let markdown = result.markdown.unwrap().raw_markdown;
+    assert!(markdown.contains("# Story") && !markdown.contains("Sidebar") && !markdown.contains("Home"));
+    assert_eq!(result.links.unwrap()["internal"].len(), 3);
+    let extracted: serde_json::Value = serde_json::from_str(&result.extracted_content.unwrap()).unwrap();
+    assert_eq!(extracted, json!([{"text": "Read on."}]));
+}
@@ -0,0 +1,92 @@
+use crawl_4ai_rs::crawler::{AsyncWebCrawler, CrawlerError};
+use crawl_4ai_rs::models::{CrawlerRunConfig, CrawlerStrategy, HttpCrawlerConfig};
+use crawl_4ai_rs::proxy_strategy::{ProxyConfig, ProxyRotationStrategy, RotationMode};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// The mock server stands in for an HTTP proxy: requests for other hosts reach it with
+// their absolute URL, which wiremock matches like any other.
+
+/// Basic credentials of alice:secret.
+const AUTHORIZATION: &str = "Basic YWxpY2U6c2VjcmV0";
+
+fn http_crawler() -> AsyncWebCrawler {
+    AsyncWebCrawler::new().with_strategy(CrawlerStrategy::Http(HttpCrawlerConfig::default()))
+}
+
+async fn mount_proxy(proxy: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(header("proxy-authorization", AUTHORIZATION))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Through the proxy</p>"))
+        .mount(proxy)
+        .await;
+}
+
+#[tokio::test]
+async fn test_http_strategy_uses_run_proxy() {
+    let proxy = MockServer::start().await;
+    mount_proxy(&proxy).await;
+
+    let config = CrawlerRunConfig {
+        proxy: Some(ProxyConfig::new(proxy.uri()).with_credentials("alice", "secret")),
+        ..Default::default()
+    };
+    let result = http_crawler().arun("http://example.invalid/page", Some(config)).await.unwrap();
+
+    assert!(result.html.contains("Through the proxy"));
+}
+
+#[tokio::test]
+async fn test_rotation_evicts_failing_proxy() {
+    let proxy = MockServer::start().await;
+    mount_proxy(&proxy).await;
+
+    // Nothing listens on port 1
+    let dead = ProxyConfig::new("http://127.0.0.1:1");
+    let alive = ProxyConfig::new(proxy.uri()).with_credentials("alice", "secret");
+    let rotation = ProxyRotationStrategy::new(vec![dead, alive.clone()], RotationMode::RoundRobin).with_max_failures(1);
+    let crawler = http_crawler().with_proxy_rotation(rotation);
+
+    let result = crawler.arun("http://example.invalid/page", None).await.unwrap();
+    assert!(result.html.contains("Through the proxy"));
+    assert_eq!(crawler.proxy_rotation().unwrap().healthy_proxies(), vec![alive]);
+}
+
+#[tokio::test]
+async fn test_rotation_without_healthy_proxies() {
+    let rotation = ProxyRotationStrategy::new(vec![ProxyConfig::new("http://127.0.0.1:1")], RotationMode::Random)
+        .with_max_failures(1);
+    let crawler = http_crawler().with_proxy_rotation(rotation);
+
+    // The first attempt evicts the only proxy, so the retry has none left
+    let result = crawler.arun("http://example.invalid/page", None).await;
+
+    match result.unwrap_err().downcast_ref::<CrawlerError>() {
+        Some(CrawlerError::NavigationError(message)) => assert!(message.contains("No healthy proxy")),
+        other => panic!("Expected NavigationError, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_browser_authenticates_to_proxy() {
+    let proxy = MockServer::start().await;
+    mount_proxy(&proxy).await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(407).insert_header("proxy-authenticate", "Basic realm=\"proxy\""))
+        .mount(&proxy)
+        .await;
+
+    let crawler = AsyncWebCrawler::new();
+    let config = CrawlerRunConfig {
+        proxy: Some(ProxyConfig::new(proxy.uri()).with_credentials("alice", "secret")),
+        ..Default::default()
+    };
+    let result = crawler.arun("http://example.invalid/page", Some(config)).await.unwrap();
+
+    assert!(result.html.contains("Through the proxy"));
+    let requests = proxy.received_requests().await.unwrap();
+    assert!(requests.iter().any(|r| r.headers.contains_key("proxy-authorization")));
+    // The temporary proxy context is gone after the crawl
+    assert!(crawler.list_sessions().await.is_empty());
+}
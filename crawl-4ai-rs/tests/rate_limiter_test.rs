@@ -0,0 +1,64 @@
+use crawl_4ai_rs::crawler::{AsyncWebCrawler, CrawlerError};
+use crawl_4ai_rs::rate_limiter::RateLimiter;
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_rate_limit_honors_retry_after() {
+    let mock_server = MockServer::start().await;
+
+    // First response asks us to come back in one second
+    Mock::given(method("GET"))
+        .and(path("/limited"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "1")
+                .set_body_string("Too Many Requests"),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/limited"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Welcome back"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new().with_rate_limiter(RateLimiter::new((0, 0), 5_000, 3));
+    let url = format!("{}/limited", mock_server.uri());
+
+    let start = Instant::now();
+    let result = crawler.arun(&url, None).await;
+
+    assert!(result.is_ok(), "Crawler should recover after backing off");
+    assert!(result.unwrap().html.contains("Welcome back"));
+    assert!(start.elapsed() >= Duration::from_secs(1), "Retry-After should be honored");
+}
+
+#[tokio::test]
+async fn test_rate_limit_retries_exhausted() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/busy"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .expect(3) // 1 initial + 2 retries
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new().with_rate_limiter(RateLimiter::new((0, 0), 200, 2));
+    let url = format!("{}/busy", mock_server.uri());
+
+    let result = crawler.arun(&url, None).await;
+
+    assert!(result.is_err(), "Crawler should give up after max_retries");
+    if let Some(CrawlerError::HttpStatusCode(code)) = result.unwrap_err().downcast_ref::<CrawlerError>() {
+        assert_eq!(*code, 503);
+    } else {
+        panic!("Expected HttpStatusCode error");
+    }
+}
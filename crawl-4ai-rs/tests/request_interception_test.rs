@@ -0,0 +1,81 @@
+use crawl_4ai_rs::crawler::AsyncWebCrawler;
+use crawl_4ai_rs::models::{CrawlerRunConfig, WaitStrategy};
+use crawl_4ai_rs::request_interception::{
+    BlockResources, InterceptAction, InterceptedRequest, MockResponse, RequestOverrides, ResourceType,
+};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_block_resources() {
+    let mock_server = MockServer::start().await;
+
+    let html = r#"<html><head>
+        <link rel="stylesheet" href="/style.css">
+        <script src="/tracker/pixel.js"></script>
+    </head><body><p>Text only</p><img src="/logo.png"></body></html>"#;
+
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(html))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    for blocked in ["/style.css", "/tracker/pixel.js", "/logo.png"] {
+        Mock::given(path(blocked))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let crawler = AsyncWebCrawler::new();
+    let config = CrawlerRunConfig {
+        block_resources: Some(BlockResources::text_only().with_url_patterns(["*/tracker/*"])),
+        ..Default::default()
+    };
+    let result = crawler.arun(&mock_server.uri(), Some(config)).await.expect("Crawl with blocking failed");
+
+    assert!(result.html.contains("Text only"));
+}
+
+#[tokio::test]
+async fn test_request_interceptor_mocks_and_modifies() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/items"))
+        .and(header("x-fixture", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("from the server"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // The page itself comes from a fixture (the server has no /page); its fetch() is sent
+    // to the server with an extra header
+    let fixture = r#"<html><body><div id="out"></div><script>
+        fetch("/api/items").then(r => r.text()).then(t => { out.textContent = t; });
+    </script></body></html>"#;
+    let crawler = AsyncWebCrawler::new().with_request_interceptor(move |request: &InterceptedRequest| {
+        match request.resource_type {
+            ResourceType::Document => InterceptAction::Fulfill(MockResponse::html(fixture)),
+            ResourceType::Fetch => {
+                let mut headers = request.headers.clone();
+                headers.insert("x-fixture".to_string(), "1".to_string());
+                InterceptAction::Modify(RequestOverrides { headers: Some(headers), ..Default::default() })
+            }
+            _ => InterceptAction::Continue,
+        }
+    });
+
+    let config = CrawlerRunConfig {
+        wait_for: Some(WaitStrategy::JsCondition(
+            "document.querySelector('#out').textContent.length > 0".to_string(),
+        )),
+        ..Default::default()
+    };
+    let result = crawler.arun(&format!("{}/page", mock_server.uri()), Some(config)).await.expect("Mocked crawl failed");
+
+    assert_eq!(result.status_code, Some(200));
+    assert!(result.html.contains("from the server"));
+}
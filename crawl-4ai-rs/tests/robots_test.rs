@@ -0,0 +1,116 @@
+use crawl_4ai_rs::crawler::{AsyncWebCrawler, CrawlerError};
+use crawl_4ai_rs::models::CrawlerRunConfig;
+use crawl_4ai_rs::robots::RobotsChecker;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_robots_disallowed_url_is_refused() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/robots.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private/\n"))
+        .expect(1) // Cached after the first fetch
+        .mount(&mock_server)
+        .await;
+
+    // The page itself must never be requested
+    Mock::given(method("GET"))
+        .and(path("/private/page"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Secret"))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new();
+    let config = CrawlerRunConfig {
+        check_robots_txt: true,
+        ..Default::default()
+    };
+    let url = format!("{}/private/page", mock_server.uri());
+
+    for _ in 0..2 {
+        let result = crawler.arun(&url, Some(config.clone())).await;
+        assert!(result.is_err(), "Disallowed URL should be refused");
+        match result.unwrap_err().downcast_ref::<CrawlerError>() {
+            Some(CrawlerError::RobotsDisallowed(refused)) => assert_eq!(refused, &url),
+            other => panic!("Expected RobotsDisallowed error, got: {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_robots_ignored_when_disabled() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/robots.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /\n"))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html><body>Hello</body></html>"))
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new();
+    let url = format!("{}/page", mock_server.uri());
+
+    let result = crawler.arun(&url, None).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_robots_server_error_disallows_and_missing_file_allows() {
+    let unavailable = MockServer::start().await;
+    Mock::given(path("/robots.txt"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&unavailable)
+        .await;
+
+    let missing = MockServer::start().await;
+    Mock::given(path("/robots.txt"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&missing)
+        .await;
+
+    let checker = RobotsChecker::default();
+    assert!(!checker.can_fetch(&format!("{}/page", unavailable.uri()), "bot").await);
+    assert!(checker.can_fetch(&format!("{}/page", missing.uri()), "bot").await);
+    // Nothing listens on port 1
+    assert!(!checker.can_fetch("http://127.0.0.1:1/page", "bot").await);
+}
+
+#[tokio::test]
+async fn test_slow_robots_does_not_block_other_origins() {
+    let slow = MockServer::start().await;
+    Mock::given(path("/robots.txt"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(3)))
+        .mount(&slow)
+        .await;
+
+    let fast = MockServer::start().await;
+    Mock::given(path("/robots.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow:\n"))
+        .mount(&fast)
+        .await;
+
+    let checker = Arc::new(RobotsChecker::default());
+    let slow_check = {
+        let checker = checker.clone();
+        let url = format!("{}/page", slow.uri());
+        tokio::spawn(async move { checker.can_fetch(&url, "bot").await })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let start = Instant::now();
+    assert!(checker.can_fetch(&format!("{}/page", fast.uri()), "bot").await);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(slow_check.await.unwrap());
+}
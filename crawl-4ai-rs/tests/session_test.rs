@@ -1,9 +1,14 @@
 use crawl_4ai_rs::crawler::AsyncWebCrawler;
-use crawl_4ai_rs::models::CrawlerRunConfig;
+use crawl_4ai_rs::models::{BrowserConfig, CrawlerRunConfig};
+use crawl_4ai_rs::storage_state::StorageState;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
 async fn test_session_reuse() {
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let url = "https://example.com";
     let session_id = "test_session_1".to_string();
 
@@ -30,3 +35,100 @@ async fn test_session_reuse() {
     // Here we just check that it runs without erroring on the logic itself.
     // Note: This test might fail in the sandbox if the browser doesn't launch.
 }
+
+#[tokio::test]
+async fn test_session_page_persists_until_killed() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Page</p>"))
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new();
+    let config = CrawlerRunConfig {
+        session_id: Some("steps".to_string()),
+        js_code: vec!["history.length".to_string()],
+        ..Default::default()
+    };
+
+    crawler.arun(&format!("{}/login", mock_server.uri()), Some(config.clone())).await.unwrap();
+    let result = crawler.arun(&format!("{}/account", mock_server.uri()), Some(config)).await.unwrap();
+
+    // The second crawl navigated the same page, so it has the first one in its history
+    let history_length = result.js_execution_result.unwrap()[0].as_u64().unwrap();
+    assert!(history_length >= 2, "history.length was {}", history_length);
+
+    assert_eq!(crawler.list_sessions().await, vec!["steps".to_string()]);
+    assert!(crawler.kill_session("steps").await);
+    assert!(crawler.list_sessions().await.is_empty());
+    assert!(!crawler.kill_session("steps").await);
+}
+
+#[tokio::test]
+async fn test_idle_sessions_are_closed() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Page</p>"))
+        .mount(&mock_server)
+        .await;
+
+    let crawler = AsyncWebCrawler::new().with_session_ttl(Duration::from_millis(100));
+    for session_id in ["a", "b"] {
+        let config = CrawlerRunConfig {
+            session_id: Some(session_id.to_string()),
+            ..Default::default()
+        };
+        crawler.arun(&mock_server.uri(), Some(config)).await.unwrap();
+    }
+    assert_eq!(crawler.list_sessions().await, vec!["a".to_string(), "b".to_string()]);
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(crawler.close_idle_sessions().await, 2);
+    assert!(crawler.list_sessions().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_session_apis_without_browser() {
+    let crawler = AsyncWebCrawler::new();
+
+    assert!(crawler.list_sessions().await.is_empty());
+    assert!(!crawler.kill_session("unknown").await);
+    assert_eq!(crawler.close_idle_sessions().await, 0);
+}
+
+#[tokio::test]
+async fn test_storage_state_round_trip() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(header("cookie", "sid=abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Welcome back</p>"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let state: StorageState = serde_json::from_value(json!({
+        "cookies": [{"name": "sid", "value": "abc", "url": mock_server.uri()}],
+        "origins": [{"origin": mock_server.uri(), "localStorage": [{"name": "token", "value": "t1"}]}]
+    }))
+    .unwrap();
+    let crawler = AsyncWebCrawler::with_config(BrowserConfig { storage_state: Some(state), ..Default::default() });
+
+    let config = CrawlerRunConfig {
+        session_id: Some("logged_in".to_string()),
+        js_code: vec!["localStorage.getItem('token')".to_string(), "localStorage.setItem('theme', 'dark')".to_string()],
+        ..Default::default()
+    };
+    let result = crawler.arun(&format!("{}/account", mock_server.uri()), Some(config)).await.unwrap();
+    assert_eq!(result.js_execution_result.unwrap()[0], json!("t1"));
+
+    let exported = crawler.export_storage_state("logged_in").await.unwrap();
+    assert!(exported.cookies.iter().any(|c| c.name == "sid" && c.value == "abc"));
+    let names: Vec<&str> = exported.origins[0].local_storage.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"token") && names.contains(&"theme"));
+
+    assert!(crawler.export_storage_state("unknown").await.is_err());
+}
@@ -0,0 +1,50 @@
+use crawl_4ai_rs::crawler::AsyncWebCrawler;
+use crawl_4ai_rs::models::CrawlerRunConfig;
+use std::sync::Arc;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_crawler_is_send_sync() {
+    assert_send_sync::<AsyncWebCrawler>();
+}
+
+#[tokio::test]
+async fn test_shared_crawler_across_tasks() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/shared"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html><body>Shared</body></html>"))
+        .mount(&mock_server)
+        .await;
+
+    let crawler = Arc::new(AsyncWebCrawler::new());
+    let url = format!("{}/shared", mock_server.uri());
+
+    // Spawned tasks require the `arun` future to be `Send`.
+    let tasks: Vec<_> = (0..4)
+        .map(|i| {
+            let crawler = crawler.clone();
+            let url = url.clone();
+            tokio::spawn(async move {
+                // Half of the tasks share a session to exercise the session map.
+                let config = CrawlerRunConfig {
+                    session_id: (i % 2 == 0).then(|| "shared_session".to_string()),
+                    ..Default::default()
+                };
+                crawler.arun(&url, Some(config)).await
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        let result = task.await.expect("Task should not panic");
+        assert!(result.is_ok(), "Crawl should succeed: {:?}", result.err());
+        assert!(result.unwrap().html.contains("Shared"));
+    }
+
+    crawler.close().await.expect("Browser should close cleanly");
+}
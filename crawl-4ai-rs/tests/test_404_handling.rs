@@ -17,7 +17,7 @@ async fn test_404_no_retry_default() {
         .mount(&mock_server)
         .await;
 
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let url = format!("{}/not_found", mock_server.uri());
 
     let result = crawler.arun(&url, None).await;
@@ -46,7 +46,7 @@ async fn test_404_retry_enabled_exhaustion() {
         .mount(&mock_server)
         .await;
 
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let config = CrawlerRunConfig {
         retry_404: true,
         ..Default::default()
@@ -79,7 +79,7 @@ async fn test_404_retry_recovery() {
         .mount(&mock_server)
         .await;
 
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let config = CrawlerRunConfig {
         retry_404: true,
         ..Default::default()
@@ -29,7 +29,7 @@ async fn test_retry_on_timeout_recovery() {
         .await;
 
     // 3. Configure Crawler
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let config = CrawlerRunConfig {
         page_timeout: Some(timeout_duration),
         ..Default::default()
@@ -66,7 +66,7 @@ async fn test_retry_exhaustion() {
         .await;
 
     // 3. Configure Crawler
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let config = CrawlerRunConfig {
         page_timeout: Some(timeout_duration),
         ..Default::default()
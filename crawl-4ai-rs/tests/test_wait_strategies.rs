@@ -52,7 +52,7 @@ async fn test_wait_timeout_logic() {
         .mount(&mock_server)
         .await;
 
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
 
     // 1. Test Fixed wait
     let start = std::time::Instant::now();
@@ -0,0 +1,123 @@
+use crawl_4ai_rs::models::BrowserConfig;
+use crawl_4ai_rs::url_seeder::{SeedingConfig, UrlSeeder};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::Write;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount(server: &MockServer, route: &str, template: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(template)
+        .mount(server)
+        .await;
+}
+
+fn urlset(base: &str, paths: &[&str]) -> String {
+    let urls: String = paths.iter().map(|p| format!("<url><loc>{}{}</loc></url>", base, p)).collect();
+    format!(r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{}</urlset>"#, urls)
+}
+
+fn page(title: &str, description: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_string(format!(
+        r#"<html><head><title>{}</title><meta name="description" content="{}"></head><body></body></html>"#,
+        title, description
+    ))
+}
+
+/// Serves a site whose URLs are spread over a sitemap index, a gzipped sitemap and an RSS feed.
+async fn mock_site() -> MockServer {
+    let server = MockServer::start().await;
+    let base = server.uri();
+
+    mount(&server, "/robots.txt", ResponseTemplate::new(200).set_body_string(format!("Sitemap: {}/sitemap_index.xml", base))).await;
+    let index = format!(
+        r#"<sitemapindex><sitemap><loc>{0}/pages.xml</loc></sitemap><sitemap><loc>{0}/posts.xml.gz</loc></sitemap></sitemapindex>"#,
+        base
+    );
+    mount(&server, "/sitemap_index.xml", ResponseTemplate::new(200).set_body_string(index)).await;
+    mount(&server, "/pages.xml", ResponseTemplate::new(200).set_body_string(urlset(&base, &["/about", "/pricing"]))).await;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(urlset(&base, &["/blog/async-rust", "/about"]).as_bytes()).unwrap();
+    mount(&server, "/posts.xml.gz", ResponseTemplate::new(200).set_body_bytes(encoder.finish().unwrap())).await;
+
+    let home = r#"<html><head><link rel="alternate" type="application/rss+xml" href="/feed.xml"></head></html>"#;
+    mount(&server, "/", ResponseTemplate::new(200).set_body_string(home)).await;
+    let feed = r#"<rss><channel><item><link>/blog/tokio-tasks</link></item></channel></rss>"#;
+    mount(&server, "/feed.xml", ResponseTemplate::new(200).set_body_string(feed)).await;
+
+    mount(&server, "/about", page("About us", "Our team and history")).await;
+    mount(&server, "/pricing", page("Pricing", "Plans for every team")).await;
+    mount(&server, "/blog/async-rust", page("Async Rust", "Writing async code with tokio")).await;
+    mount(&server, "/blog/tokio-tasks", page("Spawning tasks", "How the tokio scheduler runs tasks")).await;
+
+    server
+}
+
+fn paths(urls: &[crawl_4ai_rs::url_seeder::SeededUrl], base: &str) -> Vec<String> {
+    urls.iter().map(|u| u.url.replacen(base, "", 1)).collect()
+}
+
+#[tokio::test]
+async fn test_seeder_collects_sitemaps_and_feeds() {
+    let server = mock_site().await;
+    let base = server.uri();
+
+    let urls = UrlSeeder::new().urls(&base, &SeedingConfig::default()).await.unwrap();
+
+    // The feed is read before the sitemaps nested in the index, and `/about` is listed twice
+    assert_eq!(
+        paths(&urls, &base),
+        vec!["/blog/tokio-tasks", "/about", "/pricing", "/blog/async-rust"]
+    );
+    assert!(urls.iter().all(|u| u.head.is_none() && u.relevance_score.is_none()));
+
+    let config = SeedingConfig {
+        patterns: vec!["*/blog/*".to_string()],
+        use_feeds: false,
+        ..Default::default()
+    };
+    let urls = UrlSeeder::new().urls(&base, &config).await.unwrap();
+    assert_eq!(paths(&urls, &base), vec!["/blog/async-rust"]);
+}
+
+#[tokio::test]
+async fn test_seeder_ranks_by_head_metadata() {
+    let server = mock_site().await;
+    let base = server.uri();
+
+    let config = SeedingConfig {
+        extract_head: true,
+        query: Some("tokio scheduler".to_string()),
+        score_threshold: Some(0.1),
+        ..Default::default()
+    };
+    let urls = UrlSeeder::new().urls(&base, &config).await.unwrap();
+
+    assert_eq!(paths(&urls, &base), vec!["/blog/tokio-tasks", "/blog/async-rust"]);
+    assert_eq!(urls[0].head.as_ref().unwrap().title.as_deref(), Some("Spawning tasks"));
+    assert!(urls[0].relevance_score > urls[1].relevance_score);
+}
+
+#[tokio::test]
+async fn test_seeder_uses_browser_identity() {
+    let server = mock_site().await;
+    let base = server.uri();
+
+    let browser_config = BrowserConfig {
+        user_agent: Some("seeder-test-bot".to_string()),
+        ..Default::default()
+    };
+    let config = SeedingConfig {
+        extract_head: true,
+        ..Default::default()
+    };
+    UrlSeeder::with_config(&browser_config).urls(&base, &config).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let pages: Vec<_> = requests.iter().filter(|r| r.url.path() != "/robots.txt").collect();
+    assert!(!pages.is_empty());
+    assert!(pages.iter().all(|r| r.headers.get("user-agent").unwrap() == "seeder-test-bot"));
+}
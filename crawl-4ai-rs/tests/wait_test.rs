@@ -5,7 +5,7 @@ use std::time::Instant;
 
 #[tokio::test]
 async fn test_smart_waiting_fixed() {
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let config = CrawlerRunConfig {
         wait_for: Some(WaitStrategy::Fixed(1000)),
         ..Default::default()
@@ -24,7 +24,7 @@ async fn test_smart_waiting_fixed() {
 
 #[tokio::test]
 async fn test_smart_waiting_selector() {
-    let mut crawler = AsyncWebCrawler::new();
+    let crawler = AsyncWebCrawler::new();
     let config = CrawlerRunConfig {
         wait_for: Some(WaitStrategy::Selector("#dynamic".to_string())),
         ..Default::default()
@@ -58,7 +58,7 @@ async fn test_smart_waiting_selector() {
 
 #[tokio::test]
 async fn test_smart_waiting_js() {
-     let mut crawler = AsyncWebCrawler::new();
+     let crawler = AsyncWebCrawler::new();
      let config = CrawlerRunConfig {
          wait_for: Some(WaitStrategy::JsCondition("document.body.getAttribute('data-loaded') === 'true'".to_string())),
          ..Default::default()